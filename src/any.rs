@@ -192,6 +192,8 @@ impl<'a> TryFrom<&'a XuguTypeInfo> for AnyTypeInfo {
                 ColumnType::SMALLINT => AnyTypeInfoKind::SmallInt,
                 ColumnType::INTEGER => AnyTypeInfoKind::Integer,
                 ColumnType::BIGINT => AnyTypeInfoKind::BigInt,
+                // Any 驱动没有十进制类型：按文本传递可以保留全部精度和标度，
+                // 映射为 `Double` 会丢失精度，映射为 `BigInt` 会丢失小数部分
                 ColumnType::NUMERIC => AnyTypeInfoKind::Text,
                 ColumnType::FLOAT => AnyTypeInfoKind::Real,
                 ColumnType::DOUBLE => AnyTypeInfoKind::Double,
@@ -10,6 +10,8 @@ use std::borrow::Cow;
 pub enum XuguArgumentValue<'q> {
     Null,
     Str(Cow<'q, str>),
+    /// 以文本发送的十进制数，执行预处理语句时按 `NUMERIC` 参数的精度和标度调整
+    Numeric(Cow<'q, str>),
     Bin(Cow<'q, [u8]>),
     Bytes(Bytes),
 }
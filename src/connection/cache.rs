@@ -0,0 +1,290 @@
+use super::StatementId;
+use crate::statement::XuguStatementMetadata;
+use crate::XuguConnection;
+use hashlink::LruCache;
+use sqlx_core::{Error, HashMap};
+use std::time::Duration;
+
+/// 预处理语句缓存的统计信息
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct XuguStatementCacheStats {
+    /// 在缓存中找到预处理语句的次数
+    pub hits: u64,
+    /// 缓存中没有预处理语句、需要重新准备的次数
+    pub misses: u64,
+    /// 缓存已满，最久未使用的语句被关闭的次数
+    pub evictions: u64,
+    /// 发送 `Prepare` 的次数
+    pub prepares: u64,
+    /// 所有 `Prepare` 的总耗时
+    pub prepare_time: Duration,
+    /// 单次 `Prepare` 的最长耗时
+    pub max_prepare_time: Duration,
+}
+
+impl XuguStatementCacheStats {
+    /// 缓存命中率，没有查询过缓存时返回 `None`
+    pub fn hit_ratio(&self) -> Option<f64> {
+        let total = self.hits + self.misses;
+        (total > 0).then(|| self.hits as f64 / total as f64)
+    }
+
+    /// 单次 `Prepare` 的平均耗时
+    pub fn avg_prepare_time(&self) -> Option<Duration> {
+        u32::try_from(self.prepares)
+            .ok()
+            .filter(|&n| n > 0)
+            .map(|n| self.prepare_time / n)
+    }
+}
+
+/// 缓存中的一条预处理语句
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XuguCachedStatement {
+    /// SQL 语句
+    pub sql: String,
+    /// 服务器端的预处理语句名，如 `st_sqlx_1`
+    pub statement_id: String,
+    /// 是否已固定，固定的语句不会被淘汰
+    pub pinned: bool,
+}
+
+/// 按 SQL 缓存预处理语句，缓存已满时淘汰最久未使用的语句
+///
+/// 固定的语句单独保存，不会被淘汰，但与未固定的语句一起计入容量。
+pub(crate) struct StatementCache {
+    lru: LruCache<String, (StatementId, XuguStatementMetadata)>,
+    pinned: HashMap<String, (StatementId, XuguStatementMetadata)>,
+    stats: XuguStatementCacheStats,
+}
+
+impl StatementCache {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            lru: LruCache::new(capacity),
+            pinned: HashMap::new(),
+            stats: XuguStatementCacheStats::default(),
+        }
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.capacity() > 0
+    }
+
+    fn capacity(&self) -> usize {
+        self.lru.capacity()
+    }
+
+    fn is_full(&self) -> bool {
+        self.len() >= self.capacity()
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.lru.len() + self.pinned.len()
+    }
+
+    /// 查找预处理语句，并记录命中或未命中
+    pub(crate) fn get(&mut self, sql: &str) -> Option<(StatementId, XuguStatementMetadata)> {
+        // <XuguStatementMetadata> is internally reference-counted
+        let statement = match self.pinned.get(sql) {
+            Some(statement) => Some(statement.clone()),
+            None => self.lru.get(sql).cloned(),
+        };
+
+        match statement {
+            Some(_) => self.stats.hits += 1,
+            None => self.stats.misses += 1,
+        }
+
+        statement
+    }
+
+    /// 插入预处理语句，返回被淘汰的语句
+    ///
+    /// 缓存已被固定的语句占满时不缓存，返回该语句本身。
+    pub(crate) fn insert(
+        &mut self,
+        sql: &str,
+        statement: (StatementId, XuguStatementMetadata),
+    ) -> Option<StatementId> {
+        if self.pinned.len() >= self.capacity() {
+            return Some(statement.0);
+        }
+
+        let evicted = if self.is_full() && !self.lru.contains_key(sql) {
+            self.evict_lru()
+        } else {
+            self.lru.remove(sql).map(|(id, _)| id)
+        };
+        self.lru.insert(sql.to_owned(), statement);
+
+        evicted
+    }
+
+    /// 淘汰最久未使用的未固定语句
+    fn evict_lru(&mut self) -> Option<StatementId> {
+        let (_, (id, _)) = self.lru.remove_lru()?;
+        self.stats.evictions += 1;
+        Some(id)
+    }
+
+    /// 移除一条语句，优先移除未固定的语句
+    pub(crate) fn remove_lru(&mut self) -> Option<StatementId> {
+        if let Some((_, (id, _))) = self.lru.remove_lru() {
+            return Some(id);
+        }
+
+        let sql = self.pinned.keys().next()?.clone();
+        self.pinned.remove(&sql).map(|(id, _)| id)
+    }
+
+    pub(crate) fn record_prepare(&mut self, elapsed: Duration) {
+        self.stats.prepares += 1;
+        self.stats.prepare_time += elapsed;
+        self.stats.max_prepare_time = self.stats.max_prepare_time.max(elapsed);
+    }
+}
+
+impl XuguConnection {
+    /// 预处理语句缓存的统计信息
+    pub fn statement_cache_stats(&self) -> XuguStatementCacheStats {
+        self.inner.cache_statement.stats
+    }
+
+    /// 缓存中的所有预处理语句，固定的语句在前，其余按最近使用的先后排列
+    pub fn cached_statements(&self) -> Vec<XuguCachedStatement> {
+        let cache = &self.inner.cache_statement;
+        let statement = |sql: &String, id: &StatementId, pinned| XuguCachedStatement {
+            sql: sql.clone(),
+            statement_id: id.to_string(),
+            pinned,
+        };
+
+        cache
+            .pinned
+            .iter()
+            .map(|(sql, (id, _))| statement(sql, id, true))
+            .chain(
+                cache
+                    .lru
+                    .iter()
+                    .rev()
+                    .map(|(sql, (id, _))| statement(sql, id, false)),
+            )
+            .collect()
+    }
+
+    /// 固定一条预处理语句，固定的语句不会被缓存淘汰
+    ///
+    /// 固定的语句计入 `statement_cache_capacity`：语句不在缓存中时会先准备该语句，
+    /// 缓存已满时淘汰最久未使用的未固定语句；缓存已被固定的语句占满时返回
+    /// [`Error::InvalidArgument`]。
+    pub async fn pin_statement(&mut self, sql: &str) -> Result<(), Error> {
+        let cache = &mut self.inner.cache_statement;
+        if cache.pinned.contains_key(sql) {
+            return Ok(());
+        }
+        if cache.pinned.len() >= cache.capacity() {
+            return Err(Error::InvalidArgument(format!(
+                "cannot pin statement: all {} statements allowed by statement_cache_capacity are pinned",
+                cache.capacity()
+            )));
+        }
+
+        let statement = match cache.lru.remove(sql) {
+            Some(statement) => statement,
+            None => self.prepare_statement(sql).await?,
+        };
+
+        let cache = &mut self.inner.cache_statement;
+        if cache.is_full() {
+            if let Some(id) = cache.evict_lru() {
+                // sent together with the next command
+                self.inner.pending_stmt_close.push(id);
+            }
+        }
+        cache.pinned.insert(sql.to_owned(), statement);
+
+        Ok(())
+    }
+
+    /// 取消固定，语句重新由缓存按最近使用的顺序管理
+    ///
+    /// 返回该语句之前是否已固定。
+    pub fn unpin_statement(&mut self, sql: &str) -> bool {
+        let cache = &mut self.inner.cache_statement;
+        let Some(statement) = cache.pinned.remove(sql) else {
+            return false;
+        };
+
+        if let Some(id) = cache.insert(sql, statement) {
+            // sent together with the next command
+            self.inner.pending_stmt_close.push(id);
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connection::mock::{self, block_on, MockServer};
+    use sqlx_core::connection::Connection;
+
+    fn statement(id: &mut StatementId) -> (StatementId, XuguStatementMetadata) {
+        let current = *id;
+        *id = id.next();
+        (current, XuguStatementMetadata::default())
+    }
+
+    #[test]
+    fn pinned_statements_count_toward_capacity() {
+        let server = MockServer::new(|_| mock::ok());
+        let mut conn = server.connect();
+        conn.inner.cache_statement = StatementCache::new(2);
+        let mut id = StatementId::NAMED_START;
+
+        // 缓存已满时固定新语句，淘汰最久未使用的语句
+        let cache = &mut conn.inner.cache_statement;
+        assert_eq!(cache.insert("a", statement(&mut id)), None);
+        assert_eq!(cache.insert("b", statement(&mut id)), None);
+        block_on(conn.pin_statement("c")).unwrap();
+        assert_eq!(conn.inner.pending_stmt_close, [StatementId::NAMED_START]);
+        assert_eq!(conn.cached_statements_size(), 2);
+
+        block_on(conn.pin_statement("b")).unwrap();
+        assert_eq!(conn.cached_statements_size(), 2);
+
+        // 缓存被固定的语句占满
+        let err = block_on(conn.pin_statement("d")).unwrap_err();
+        assert!(matches!(err, Error::InvalidArgument(_)), "{err:?}");
+
+        // 不再缓存新语句，直接返回以便关闭
+        let (new_id, metadata) = statement(&mut id);
+        let cache = &mut conn.inner.cache_statement;
+        assert_eq!(cache.insert("e", (new_id, metadata)), Some(new_id));
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get("e").is_none());
+
+        // 取消固定后重新由 LRU 管理
+        assert!(conn.unpin_statement("b"));
+        let cache = &mut conn.inner.cache_statement;
+        let evicted = cache.insert("f", statement(&mut id));
+        assert!(evicted.is_some());
+        assert!(cache.get("f").is_some());
+        assert!(cache.get("c").is_some());
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn zero_capacity_disables_cache() {
+        let mut cache = StatementCache::new(0);
+        let mut id = StatementId::NAMED_START;
+        assert!(!cache.is_enabled());
+
+        let (new_id, metadata) = statement(&mut id);
+        assert_eq!(cache.insert("a", (new_id, metadata)), Some(new_id));
+        assert_eq!(cache.len(), 0);
+    }
+}
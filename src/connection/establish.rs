@@ -1,34 +1,133 @@
+use super::session::{quote, set_sql};
 use super::stream::XuguStream;
-use super::{StatementId, XuguConnection, XuguConnectionInner};
+use super::{
+    ResponseState, SessionSettings, StatementCache, StatementId, XuguConnection,
+    XuguConnectionInner,
+};
+use crate::protocol::text::Query;
 use crate::XuguConnectOptions;
-use sqlx_core::common::StatementCache;
 use sqlx_core::Error;
 
 impl XuguConnection {
     pub(crate) async fn establish(options: &XuguConnectOptions) -> Result<Self, Error> {
         let stream = do_handshake(options).await?;
 
+        let mut conn = Self::from_stream(stream, options);
+        conn.after_connect(options).await?;
+
+        Ok(conn)
+    }
+
+    /// 使用已完成登录的连接创建 `XuguConnection`
+    pub(super) fn from_stream(stream: XuguStream, options: &XuguConnectOptions) -> Self {
+        let session = SessionSettings::from_options(options);
+
         let inner = XuguConnectionInner {
             stream,
             transaction_depth: 0,
+            xa_xid: None,
             next_statement_id: StatementId::NAMED_START,
             cache_statement: StatementCache::new(options.statement_cache_capacity),
             pending_ready_for_query_count: 0,
-            last_num_columns: 0,
+            pending_stmt_close: Vec::new(),
+            state: ResponseState::default(),
+            max_row_bytes: options.max_row_bytes,
+            numeric_rounding: options.numeric_rounding,
+            time_zone: session.time_zone_offset(),
+            initial_session: session.clone(),
+            session,
             log_settings: options.log_settings.clone(),
         };
 
-        Ok(Self {
+        Self {
             inner: Box::new(inner),
-        })
+        }
+    }
+
+    /// 登录成功后设置客户端信息，并执行初始化 SQL
+    async fn after_connect(&mut self, options: &XuguConnectOptions) -> Result<(), Error> {
+        // 仅在显式配置时发送；服务器不支持这些会话参数时忽略错误，不影响建立连接
+        let client_info = [
+            ("application_name", &options.application_name),
+            ("client_info", &options.client_info),
+        ];
+        for (name, value) in client_info {
+            let Some(value) = value else { continue };
+            match self.simple_query(&set_sql(name, &quote(value))).await {
+                Ok(()) => {}
+                Err(Error::Database(e)) => {
+                    tracing::debug!(
+                        target: "sqlx::xugu::notice",
+                        "failed to set session parameter {name}: {e}"
+                    );
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        for sql in &options.init_sql {
+            self.simple_query(sql).await?;
+        }
+
+        Ok(())
+    }
+
+    /// 以普通查询执行一条语句，不经过预处理，不占用预处理语句缓存
+    async fn simple_query(&mut self, sql: &str) -> Result<(), Error> {
+        self.wait_until_ready().await?;
+        self.send_command(Query(sql)).await?;
+        self.wait_until_ready().await
     }
 }
 
 async fn do_handshake(options: &XuguConnectOptions) -> Result<XuguStream, Error> {
     let mut stream = XuguStream::connect(options).await?;
-    let conn_str = options.to_conn_str();
+    let password = options.resolve_password().await?;
+    let conn_str = options.to_conn_str(&password)?;
     let opts_version = options.get_version();
     stream.do_handshake(&conn_str, opts_version).await?;
 
     Ok(stream)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connection::mock::{self, block_on, MockServer};
+    use sqlx_core::connection::Connection;
+
+    #[test]
+    fn after_connect_ignores_unsupported_client_info() {
+        let server = MockServer::new(|command| {
+            if command.sql.starts_with("SET application_name") {
+                [mock::error("E19132 unknown session parameter"), mock::ok()].concat()
+            } else {
+                mock::ok()
+            }
+        });
+        let options = XuguConnectOptions::new()
+            .application_name("app")
+            .init_sql("SET lock_timeout TO 10");
+        let mut conn = server.connect_with(&options);
+
+        block_on(conn.after_connect(&options)).unwrap();
+        block_on(conn.ping()).unwrap();
+
+        assert_eq!(
+            server.sql()[..2],
+            ["SET application_name TO 'app'", "SET lock_timeout TO 10"]
+        );
+        assert!(server.commands().iter().all(|c| c.params.is_empty()));
+        assert_eq!(conn.cached_statements_size(), 0);
+    }
+
+    #[test]
+    fn after_connect_fails_on_init_sql_error() {
+        let server = MockServer::new(|_| [mock::error("E1 syntax error"), mock::ok()].concat());
+        let options = XuguConnectOptions::new().init_sql("SET bogus");
+        let mut conn = server.connect_with(&options);
+
+        let err = block_on(conn.after_connect(&options)).unwrap_err();
+        assert!(matches!(err, Error::Database(_)));
+    }
+}
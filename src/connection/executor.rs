@@ -1,10 +1,7 @@
-use crate::connection::StatementId;
+use crate::connection::{Response, StatementId};
 use crate::error::Error;
-use crate::io::AsyncStreamExt;
-use crate::protocol::message::*;
-use crate::protocol::statement::{Execute as StatementExecute, Prepare, StmtClose};
-use crate::protocol::text::{ColumnFlags, OkPacket, Query};
-use crate::protocol::ServerContext;
+use crate::protocol::statement::{Execute as StatementExecute, ExecuteDirect, Prepare};
+use crate::protocol::text::{ColumnFlags, Query};
 use crate::statement::{XuguStatement, XuguStatementMetadata};
 use crate::{
     Xugu, XuguArguments, XuguConnection, XuguDatabaseError, XuguQueryResult, XuguRow, XuguTypeInfo,
@@ -13,29 +10,31 @@ use futures_core::future::BoxFuture;
 use futures_core::stream::BoxStream;
 use futures_core::Stream;
 use futures_util::TryStreamExt;
-use log::Level;
 use sqlx_core::describe::Describe;
 use sqlx_core::executor::{Execute, Executor};
 use sqlx_core::logger::QueryLogger;
 use sqlx_core::{try_stream, Either, HashMap};
+use std::time::Instant;
 use std::{borrow::Cow, pin::pin, sync::Arc};
 
 impl XuguConnection {
-    async fn prepare_statement(
+    pub(super) async fn prepare_statement(
         &mut self,
         sql: &str,
     ) -> Result<(StatementId, XuguStatementMetadata), Error> {
         // flush and wait until we are re-ready
         self.wait_until_ready().await?;
 
+        let started = Instant::now();
         let id = self.inner.gen_st_id();
-        self.inner
-            .stream
-            .send_packet(Prepare {
-                query: sql,
-                st_id: id,
-            })
-            .await?;
+        // 不附带等待关闭的语句：它们的应答排在预处理的响应之后，
+        // 本方法读到预处理的 `K` 即返回，随后的执行会把关闭语句的 `K` 误认为自己的结束
+        self.inner.stream.write_packet(Prepare {
+            query: sql,
+            st_id: id,
+        })?;
+        self.inner.pending_ready_for_query_count += 1;
+        self.inner.stream.flush().await?;
 
         let mut error = None;
         let mut columns = Vec::new();
@@ -43,46 +42,19 @@ impl XuguConnection {
         let mut params = Vec::new();
 
         loop {
-            let message: ReceivedMessage = self.inner.stream.recv().await?;
-            let cnt = ServerContext::new(self.inner.stream.server_version);
-            match message.format {
-                BackendMessageFormat::ErrorResponse => {
-                    let err: ErrorResponse = message.decode(&mut self.inner.stream, cnt).await?;
+            match self.recv_response().await? {
+                Response::Error(err) => {
                     error = Some(err.error);
                 }
-                BackendMessageFormat::MessageResponse => {
-                    // 读到服务器端返回消息用对话框抛出
-                    // 警告和信息
-                    let notice: MessageResponse =
-                        message.decode(&mut self.inner.stream, cnt).await?;
-                    let (log_level, tracing_level) = (Level::Info, tracing::Level::INFO);
-                    let log_is_enabled = log::log_enabled!(
-                        target: "sqlx::xugu::notice",
-                        log_level
-                    ) || sqlx_core::private_tracing_dynamic_enabled!(
-                        target: "sqlx::xugu::notice",
-                        tracing_level
-                    );
-                    if log_is_enabled {
-                        sqlx_core::private_tracing_dynamic_event!(
-                            target: "sqlx::xugu::notice",
-                            tracing_level,
-                            message = notice.msg
-                        );
-                    }
-                }
-                BackendMessageFormat::ReadyForQuery => {
-                    let _: ReadyForQuery = message.decode(&mut self.inner.stream, cnt).await?;
+                Response::Notice(_) => {}
+                Response::ReadyForQuery => {
+                    self.handle_ready_for_query().await?;
                     break;
                 }
-                BackendMessageFormat::RowDescription => {
-                    let row_columns: RowDescription =
-                        message.decode(&mut self.inner.stream, cnt).await?;
+                Response::RowDescription(row_columns) => {
                     (columns, column_names) = row_columns.convert_columns()?;
                 }
-                BackendMessageFormat::ParameterDescription => {
-                    let param_def: ParameterDescription =
-                        message.decode(&mut self.inner.stream, cnt).await?;
+                Response::ParameterDescription(param_def) => {
                     params = param_def.params;
                 }
                 _ => {
@@ -91,6 +63,8 @@ impl XuguConnection {
             }
         }
 
+        self.inner.cache_statement.record_prepare(started.elapsed());
+
         if let Some(err) = error {
             return Err(Error::Database(Box::new(XuguDatabaseError::from_str(&err))));
         }
@@ -108,25 +82,20 @@ impl XuguConnection {
         &mut self,
         sql: &str,
     ) -> Result<(StatementId, XuguStatementMetadata), Error> {
-        if let Some(statement) = self.inner.cache_statement.get_mut(sql) {
-            // <XuguStatementMetadata> is internally reference-counted
-            return Ok((*statement).clone());
+        if let Some(statement) = self.inner.cache_statement.get(sql) {
+            return Ok(statement);
         }
 
         let (id, metadata) = self.prepare_statement(sql).await?;
 
         // in case of the cache being full, close the least recently used statement
-        if let Some((id, _)) = self
+        if let Some(id) = self
             .inner
             .cache_statement
             .insert(sql, (id, metadata.clone()))
         {
-            // flush and wait until we are re-ready
-            self.wait_until_ready().await?;
-            self.inner.stream.send_packet(StmtClose(id)).await?;
-
-            // for StmtClose
-            let _ok: OkPacket = self.inner.stream.recv().await?;
+            // sent together with the next command
+            self.inner.pending_stmt_close.push(id);
         }
 
         Ok((id, metadata))
@@ -160,118 +129,75 @@ impl XuguConnection {
             if persistent && self.inner.cache_statement.is_enabled() {
                 let (id, metadata) = self.get_or_prepare_statement(sql).await?;
 
-                self.inner
-                    .stream
-                    .send_packet(StatementExecute {
-                        st_id: id,
-                        arguments: &arguments,
-                        params: &metadata.parameters,
-                    })
-                    .await?;
+                self.send_command(StatementExecute {
+                    st_id: id,
+                    arguments: &arguments,
+                    params: &metadata.parameters,
+                    numeric_rounding: self.inner.numeric_rounding,
+                })
+                .await?;
 
                 let needs_metadata = metadata.column_names.is_empty();
                 (metadata.column_names, metadata.columns, needs_metadata)
             } else {
-                let (id, metadata) = self.prepare_statement(sql).await?;
-
-                self.inner
-                    .stream
-                    .send_packet(StatementExecute {
-                        st_id: id,
-                        arguments: &arguments,
-                        params: &metadata.parameters,
-                    })
-                    .await?;
-
-                self.inner.stream.send_packet(StmtClose(id)).await?;
-                // for StmtClose
-                self.inner.pending_ready_for_query_count += 1;
+                // 不缓存的语句直接带参数执行，只需一次往返
+                self.send_command(ExecuteDirect {
+                    sql,
+                    arguments: &arguments,
+                })
+                .await?;
 
-                let needs_metadata = metadata.column_names.is_empty();
-                (metadata.column_names, metadata.columns, needs_metadata)
+                (Arc::default(), Arc::default(), true)
             }
         } else {
-            self.inner.stream.send_packet(Query(sql)).await?;
+            self.send_command(Query(sql)).await?;
 
             (Arc::default(), Arc::default(), true)
         };
 
-        self.inner.pending_ready_for_query_count += 1;
-
         let mut error = None;
 
-        let mut num_columns = 0;
+        let time_zone = self.inner.time_zone;
 
         Ok(try_stream! {
             loop {
-                let message: ReceivedMessage = self.inner.stream.recv().await?;
-                let cnt = ServerContext::new(self.inner.stream.server_version);
-                match message.format {
-                    BackendMessageFormat::ErrorResponse => {
-                        let err: ErrorResponse = message.decode(&mut self.inner.stream, cnt).await?;
+                let v = match self.recv_response().await? {
+                    Response::Error(err) => {
                         error = Some(err.error);
+                        None
                     },
-                    BackendMessageFormat::MessageResponse => {
-                        // 读到服务器端返回消息用对话框抛出
-                        // 警告和信息
-                        let notice: MessageResponse = message.decode(&mut self.inner.stream, cnt).await?;
-                        let (log_level, tracing_level) = (Level::Info, tracing::Level::INFO);
-                        let log_is_enabled = log::log_enabled!(
-                            target: "sqlx::xugu::notice",
-                            log_level
-                        ) || sqlx_core::private_tracing_dynamic_enabled!(
-                            target: "sqlx::xugu::notice",
-                            tracing_level
-                        );
-                        if log_is_enabled {
-                            sqlx_core::private_tracing_dynamic_event!(
-                                target: "sqlx::xugu::notice",
-                                tracing_level,
-                                message = notice.msg
-                            );
-                        }
-                    },
-                    BackendMessageFormat::ReadyForQuery => {
+                    Response::Notice(_) => None,
+                    Response::ReadyForQuery => {
                         //命令结束 / 错误结束
-                        let _: ReadyForQuery = message.decode(&mut self.inner.stream, cnt).await?;
                         self.handle_ready_for_query().await?;
                         break;
                     },
-                    BackendMessageFormat::InsertResponse => {
-                        let res: InsertResponse = message.decode(&mut self.inner.stream, cnt).await?;
+                    Response::Insert(res) => {
                         let rows_affected = 1;
                         logger.increase_rows_affected(rows_affected);
-                        let done = XuguQueryResult {
+                        Some(Either::Left(XuguQueryResult {
                             rows_affected,
                             last_insert_id: Some(res.rowid),
-                        };
-                        r#yield!(Either::Left(done));
+                        }))
                     },
-                    BackendMessageFormat::DeleteResponse => {
-                        let res: DeleteResponse = message.decode(&mut self.inner.stream, cnt).await?;
+                    Response::Delete(res) => {
                         let rows_affected = res.rows_affected as u64;
                         logger.increase_rows_affected(rows_affected);
-                        let done = XuguQueryResult {
+                        Some(Either::Left(XuguQueryResult {
                             rows_affected,
                             last_insert_id: None,
-                        };
-                        r#yield!(Either::Left(done));
+                        }))
                     },
-                    BackendMessageFormat::UpdateResponse => {
-                        let res: UpdateResponse = message.decode(&mut self.inner.stream, cnt).await?;
+                    Response::Update(res) => {
                         let rows_affected = res.rows_affected as u64;
                         logger.increase_rows_affected(rows_affected);
-                        let done = XuguQueryResult {
+                        Some(Either::Left(XuguQueryResult {
                             rows_affected,
                             last_insert_id: None,
-                        };
-                        r#yield!(Either::Left(done));
+                        }))
                     },
-                    BackendMessageFormat::RowDescription => {
+                    Response::RowDescription(row_columns) => {
                         // 接收列数据
-                        let row_columns: RowDescription = message.decode(&mut self.inner.stream, cnt).await?;
-                        num_columns = row_columns.fields.len();
-                        self.inner.last_num_columns = num_columns;
                         if needs_metadata {
                             let (columns_c, column_names_c) = row_columns.convert_columns()?;
                             columns = Arc::new(columns_c);
@@ -281,31 +207,24 @@ impl XuguConnection {
                             // full metadata
                             needs_metadata = true;
                         }
+                        None
                     },
-                    BackendMessageFormat::ParameterDescription => {
-                        let _: ParameterDescription = message.decode(&mut self.inner.stream, cnt).await?;
-                    },
-                    BackendMessageFormat::DataRow => {
+                    Response::ParameterDescription(_) => None,
+                    Response::DataRow(row) => {
                         // 接收行数据
-                        let _: DataRow = message.decode(&mut self.inner.stream, cnt).await?;
-                        let mut row = Vec::with_capacity(num_columns);
-                        for _ in 0..num_columns {
-                            let len = self.inner.stream.read_i32().await?;
-                            let buf = self.inner.stream.read_bytes(len as usize).await?;
-                            row.push(buf);
-                        }
-                        let row = Arc::new(row);
+                        logger.increment_rows_returned();
 
-                        let v = Either::Right(XuguRow {
-                            row,
+                        Some(Either::Right(XuguRow {
+                            row: Arc::new(row),
                             columns: Arc::clone(&columns),
                             column_names: Arc::clone(&column_names),
-                        });
-
-                        logger.increment_rows_returned();
-
-                        r#yield!(v);
+                            time_zone,
+                        }))
                     }
+                };
+
+                if let Some(v) = v {
+                    r#yield!(v);
                 }
             }
 
@@ -387,10 +306,7 @@ impl<'c> Executor<'c> for &'c mut XuguConnection {
                 self.get_or_prepare_statement(sql).await?.1
             } else {
                 let (id, metadata) = self.prepare_statement(sql).await?;
-
-                self.inner.stream.send_packet(StmtClose(id)).await?;
-                // for StmtClose
-                let _ok: OkPacket = self.inner.stream.recv().await?;
+                self.inner.pending_stmt_close.push(id);
 
                 metadata
             };
@@ -415,10 +331,7 @@ impl<'c> Executor<'c> for &'c mut XuguConnection {
             self.wait_until_ready().await?;
 
             let (id, metadata) = self.prepare_statement(sql).await?;
-
-            self.inner.stream.send_packet(StmtClose(id)).await?;
-            // for StmtClose
-            let _ok: OkPacket = self.inner.stream.recv().await?;
+            self.inner.pending_stmt_close.push(id);
 
             let columns = (*metadata.columns).clone();
 
@@ -438,3 +351,61 @@ impl<'c> Executor<'c> for &'c mut XuguConnection {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::connection::mock::{self, block_on, Command, MockServer};
+    use crate::protocol::text::ColumnType;
+    use sqlx_core::executor::Executor;
+    use sqlx_core::row::Row;
+
+    const SQL: &str = "SELECT N FROM T WHERE N = ?";
+
+    /// 预处理返回参数和字段定义，执行时返回参数值对应的一行
+    fn handler(command: &Command) -> Vec<u8> {
+        if command.sql.starts_with("Prepare ") {
+            [
+                mock::params(&[("", ColumnType::INTEGER, 0, 0)]),
+                mock::columns(&[("N", ColumnType::INTEGER)]),
+                mock::ok(),
+            ]
+            .concat()
+        } else if command.sql.starts_with("? ") {
+            [mock::row(&[&command.params[0].value]), mock::ok()].concat()
+        } else {
+            mock::ok()
+        }
+    }
+
+    #[test]
+    fn describe_then_query() {
+        let server = MockServer::new(handler);
+        let mut conn = server.connect();
+
+        block_on(async {
+            let describe = (&mut conn).describe(SQL).await?;
+            assert_eq!(describe.columns.len(), 1);
+
+            // 缓存未命中，预处理后执行；describe 留下的关闭命令不能干扰执行的响应
+            for n in [7_i32, 8] {
+                let rows = sqlx_core::query::query(SQL)
+                    .bind(n)
+                    .fetch_all(&mut conn)
+                    .await?;
+                assert_eq!(rows.len(), 1);
+                assert_eq!(rows[0].get::<i32, _>(0), n);
+            }
+
+            Ok::<_, sqlx_core::Error>(())
+        })
+        .unwrap();
+
+        let sql = server.sql();
+        assert!(
+            sql.iter().any(|sql| sql == "deallocate st_sqlx_1"),
+            "{sql:?}"
+        );
+        assert_eq!(conn.inner.pending_ready_for_query_count, 0);
+        assert_eq!(server.unread(), 0);
+    }
+}
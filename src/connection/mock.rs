@@ -0,0 +1,343 @@
+//! 测试用的模拟服务器
+//!
+//! 客户端写入的每条命令都交给处理函数，处理函数返回的响应立即排入待读取的数据；
+//! 可以限制可读、可写的字节数，模拟网络阻塞，用于测试 future 在任意位置被取消的情况。
+
+// 各模块的测试只用到其中一部分
+#![allow(dead_code)]
+
+use super::ssl::TurningComputer;
+use super::stream::XuguStream;
+use super::XuguConnection;
+use crate::protocol::text::ColumnType;
+use crate::XuguConnectOptions;
+use bytes::{Buf, BufMut};
+use sqlx_core::io::ReadBuf;
+use sqlx_core::net::Socket;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::io;
+use std::pin::{pin, Pin};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+/// 客户端发送的一条命令
+#[derive(Debug, Clone)]
+pub(crate) struct Command {
+    pub(crate) sql: String,
+    pub(crate) params: Vec<Param>,
+}
+
+/// 命令携带的参数
+#[derive(Debug, Clone)]
+pub(crate) struct Param {
+    pub(crate) name: String,
+    pub(crate) inout: i16,
+    pub(crate) type_id: i16,
+    pub(crate) value: Vec<u8>,
+}
+
+type Handler = Box<dyn FnMut(&Command) -> Vec<u8> + Send>;
+
+struct Shared {
+    handler: Handler,
+    /// 客户端已发送、尚未组成完整命令的数据（已解密）
+    received: Vec<u8>,
+    commands: Vec<Command>,
+    /// 等待客户端读取的数据（已加密）
+    outgoing: VecDeque<u8>,
+    read_budget: Option<usize>,
+    write_budget: Option<usize>,
+    /// 加密时服务器一侧的密钥流：(解密客户端数据, 加密响应)
+    ssl: Option<(TurningComputer, TurningComputer)>,
+}
+
+#[derive(Clone)]
+pub(crate) struct MockServer {
+    shared: Arc<Mutex<Shared>>,
+    ssl_key: Option<[u8; 32]>,
+}
+
+impl MockServer {
+    pub(crate) fn new(handler: impl FnMut(&Command) -> Vec<u8> + Send + 'static) -> Self {
+        Self {
+            shared: Arc::new(Mutex::new(Shared {
+                handler: Box::new(handler),
+                received: Vec::new(),
+                commands: Vec::new(),
+                outgoing: VecDeque::new(),
+                read_budget: None,
+                write_budget: None,
+                ssl: None,
+            })),
+            ssl_key: None,
+        }
+    }
+
+    /// 使用给定的会话密钥加密通信
+    pub(crate) fn with_ssl(
+        handler: impl FnMut(&Command) -> Vec<u8> + Send + 'static,
+        key: [u8; 32],
+    ) -> Self {
+        let server = Self::new(handler);
+        let mut recv = TurningComputer::new();
+        let mut send = TurningComputer::new();
+        super::ssl::init_key(&mut recv, &mut send, &key);
+        server.lock().ssl = Some((recv, send));
+
+        Self {
+            ssl_key: Some(key),
+            ..server
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Shared> {
+        self.shared.lock().unwrap()
+    }
+
+    pub(crate) fn connect(&self) -> XuguConnection {
+        self.connect_with(&XuguConnectOptions::new())
+    }
+
+    pub(crate) fn connect_with(&self, options: &XuguConnectOptions) -> XuguConnection {
+        XuguConnection::from_stream(self.stream(options), options)
+    }
+
+    pub(crate) fn stream(&self, options: &XuguConnectOptions) -> XuguStream {
+        let socket = MockSocket {
+            shared: Arc::clone(&self.shared),
+        };
+        let options = options.clone().use_ssl(self.ssl_key.is_some());
+        let mut stream = XuguStream::new(Box::new(socket), &options);
+        if let Some(key) = &self.ssl_key {
+            stream.init_ssl_key(key);
+        }
+        stream
+    }
+
+    /// 已收到的全部命令的 SQL
+    pub(crate) fn sql(&self) -> Vec<String> {
+        self.lock().commands.iter().map(|c| c.sql.clone()).collect()
+    }
+
+    pub(crate) fn commands(&self) -> Vec<Command> {
+        self.lock().commands.clone()
+    }
+
+    /// 限制客户端还能读取的字节数，`None` 表示不限制
+    pub(crate) fn set_read_budget(&self, budget: Option<usize>) {
+        self.lock().read_budget = budget;
+    }
+
+    /// 限制客户端还能写入的字节数，`None` 表示不限制
+    pub(crate) fn set_write_budget(&self, budget: Option<usize>) {
+        self.lock().write_budget = budget;
+    }
+
+    /// 客户端尚未读取的字节数
+    pub(crate) fn unread(&self) -> usize {
+        self.lock().outgoing.len()
+    }
+}
+
+impl Shared {
+    fn handle_commands(&mut self) {
+        while let Some((command, len)) = parse_command(&self.received) {
+            self.received.drain(..len);
+            let mut response = (self.handler)(&command);
+            self.commands.push(command);
+            if let Some((_, send)) = &mut self.ssl {
+                send.xor_buff_exact(&mut response);
+            }
+            self.outgoing.extend(response);
+        }
+    }
+}
+
+/// 解析一条完整的命令：`'?' len sql '\0' count params...`
+fn parse_command(mut buf: &[u8]) -> Option<(Command, usize)> {
+    let total = buf.len();
+    let take = |buf: &mut &[u8], n: usize| -> Option<Vec<u8>> {
+        let bytes = buf.get(..n)?.to_vec();
+        buf.advance(n);
+        Some(bytes)
+    };
+
+    if take(&mut buf, 1)? != b"?" {
+        panic!("unexpected command from client: {buf:?}");
+    }
+    let len = u32::from_be_bytes(take(&mut buf, 4)?.try_into().unwrap()) as usize;
+    let sql = String::from_utf8(take(&mut buf, len)?).unwrap();
+    take(&mut buf, 1)?;
+
+    let count = u32::from_be_bytes(take(&mut buf, 4)?.try_into().unwrap());
+    let mut params = Vec::new();
+    for _ in 0..count {
+        let len = i16::from_be_bytes(take(&mut buf, 2)?.try_into().unwrap()) as usize;
+        let name = String::from_utf8(take(&mut buf, len)?).unwrap();
+        let inout = i16::from_be_bytes(take(&mut buf, 2)?.try_into().unwrap());
+        let type_id = i16::from_be_bytes(take(&mut buf, 2)?.try_into().unwrap());
+        let len = i32::from_be_bytes(take(&mut buf, 4)?.try_into().unwrap()) as usize;
+        let value = take(&mut buf, len)?;
+        params.push(Param {
+            name,
+            inout,
+            type_id,
+            value,
+        });
+    }
+
+    Some((Command { sql, params }, total - buf.len()))
+}
+
+struct MockSocket {
+    shared: Arc<Mutex<Shared>>,
+}
+
+fn would_block() -> io::Error {
+    io::Error::from(io::ErrorKind::WouldBlock)
+}
+
+impl Socket for MockSocket {
+    fn try_read(&mut self, buf: &mut dyn ReadBuf) -> io::Result<usize> {
+        let mut shared = self.shared.lock().unwrap();
+        let n = shared
+            .outgoing
+            .len()
+            .min(shared.read_budget.unwrap_or(usize::MAX))
+            .min(buf.remaining_mut());
+        if n == 0 {
+            return Err(would_block());
+        }
+
+        let bytes: Vec<u8> = shared.outgoing.drain(..n).collect();
+        buf.put_slice(&bytes);
+        if let Some(budget) = &mut shared.read_budget {
+            *budget -= n;
+        }
+        Ok(n)
+    }
+
+    fn try_write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut shared = self.shared.lock().unwrap();
+        let n = buf.len().min(shared.write_budget.unwrap_or(usize::MAX));
+        if n == 0 {
+            return Err(would_block());
+        }
+
+        let mut bytes = buf[..n].to_vec();
+        if let Some((recv, _)) = &mut shared.ssl {
+            recv.xor_buff_exact(&mut bytes);
+        }
+        shared.received.extend(bytes);
+        if let Some(budget) = &mut shared.write_budget {
+            *budget -= n;
+        }
+        shared.handle_commands();
+        Ok(n)
+    }
+
+    fn poll_read_ready(&mut self, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let shared = self.shared.lock().unwrap();
+        if shared.outgoing.is_empty() || shared.read_budget == Some(0) {
+            Poll::Pending
+        } else {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    fn poll_write_ready(&mut self, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        if self.shared.lock().unwrap().write_budget == Some(0) {
+            Poll::Pending
+        } else {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    fn poll_shutdown(&mut self, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// 轮询 future，模拟服务器的数据或写入额度耗尽时返回 `None`
+pub(crate) fn poll_until_blocked<F: Future>(fut: Pin<&mut F>) -> Option<F::Output> {
+    let waker = futures_util::task::noop_waker();
+    let mut cx = Context::from_waker(&waker);
+    let mut fut = fut;
+    // 模拟的 socket 只在没有数据时返回 `Pending`，多轮询几次以防个别的让出
+    for _ in 0..16 {
+        if let Poll::Ready(output) = fut.as_mut().poll(&mut cx) {
+            return Some(output);
+        }
+    }
+    None
+}
+
+/// 运行 future 直到完成，模拟服务器没有更多数据时 panic
+pub(crate) fn block_on<F: Future>(fut: F) -> F::Output {
+    poll_until_blocked(pin!(fut)).expect("the mock server has no data for the client")
+}
+
+/// 命令执行成功 `K`
+pub(crate) fn ok() -> Vec<u8> {
+    b"K".to_vec()
+}
+
+/// 错误消息 `E`
+pub(crate) fn error(msg: &str) -> Vec<u8> {
+    let mut buf = vec![b'E'];
+    put_str(&mut buf, msg);
+    buf
+}
+
+/// 更新的行数 `U`
+pub(crate) fn updated(rows: i32) -> Vec<u8> {
+    let mut buf = vec![b'U'];
+    buf.put_i32(rows);
+    buf
+}
+
+/// 字段定义 `A`
+pub(crate) fn columns(columns: &[(&str, ColumnType)]) -> Vec<u8> {
+    let mut buf = vec![b'A'];
+    buf.put_i32(columns.len() as i32);
+    for (name, ty) in columns {
+        put_str(&mut buf, name);
+        buf.put_i32(*ty as i32);
+        buf.put_i32(0);
+        buf.put_i32(0);
+    }
+    buf
+}
+
+/// 行数据 `R`，值为服务器发送的原始字节，如整数为大端序
+pub(crate) fn row(values: &[&[u8]]) -> Vec<u8> {
+    let mut buf = vec![b'R'];
+    for value in values {
+        buf.put_i32(value.len() as i32);
+        buf.put_slice(value);
+    }
+    buf
+}
+
+/// 参数定义 `$`：参数名、类型、精度和标度，全部为输入参数
+pub(crate) fn params(params: &[(&str, ColumnType, i32, i32)]) -> Vec<u8> {
+    let mut buf = vec![b'$'];
+    buf.put_i32(params.len() as i32);
+    for (name, ty, precision, scale) in params {
+        put_str(&mut buf, name);
+        buf.put_i32(0);
+        buf.put_i32(*ty as i32);
+        if *ty == ColumnType::NUMERIC {
+            buf.put_i32((precision << 16) | scale);
+        } else {
+            buf.put_i32(*precision);
+        }
+    }
+    buf
+}
+
+fn put_str(buf: &mut Vec<u8>, s: &str) {
+    buf.put_i32(s.len() as i32);
+    buf.put_slice(s.as_bytes());
+}
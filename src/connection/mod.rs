@@ -1,27 +1,35 @@
 use self::stream::XuguStream;
 pub(crate) use crate::connection::id::StatementId;
-use crate::io::AsyncStreamExt;
-use crate::protocol::message::*;
 use crate::protocol::statement::StmtClose;
-use crate::protocol::text::{OkPacket, Ping};
-use crate::protocol::ServerContext;
-use crate::statement::XuguStatementMetadata;
-use crate::{Xugu, XuguConnectOptions, XuguDatabaseError};
+use crate::protocol::text::Ping;
+use crate::{Xugu, XuguConnectOptions, XuguDatabaseError, XuguNumericRounding};
 use futures_core::future::BoxFuture;
-use futures_util::FutureExt;
-use log::Level;
-use sqlx_core::common::StatementCache;
 use sqlx_core::connection::{Connection, LogSettings};
+use sqlx_core::io::ProtocolEncode;
 use sqlx_core::transaction::Transaction;
 use sqlx_core::{err_protocol, Error};
 use std::borrow::Cow;
 use std::fmt::{Debug, Formatter};
 
+mod cache;
 mod establish;
 mod executor;
 mod id;
+#[cfg(test)]
+mod mock;
+mod response;
+mod session;
 mod ssl;
 mod stream;
+mod xa;
+
+use cache::StatementCache;
+pub use cache::{XuguCachedStatement, XuguStatementCacheStats};
+pub(crate) use response::{Response, ResponseState};
+pub(crate) use session::SessionSettings;
+pub use session::XuguPoolOptionsExt;
+pub use ssl::server_key_fingerprint;
+pub use xa::XuguXid;
 
 pub struct XuguConnection {
     pub(crate) inner: Box<XuguConnectionInner>,
@@ -32,17 +40,37 @@ pub(crate) struct XuguConnectionInner {
 
     // transaction status
     pub(crate) transaction_depth: usize,
+    // the XA branch opened by `begin_xa`, until it is prepared or ends
+    pub(crate) xa_xid: Option<XuguXid>,
     // status_flags: Status,
 
     // sequence of statement IDs for use in preparing statements
     next_statement_id: StatementId,
 
     // cache by query string to the statement id and metadata
-    cache_statement: StatementCache<(StatementId, XuguStatementMetadata)>,
+    cache_statement: StatementCache,
 
     // number of ReadyForQuery messages that we are currently expecting
     pub(crate) pending_ready_for_query_count: usize,
-    pub(crate) last_num_columns: usize,
+
+    // statements to close, sent together with the next command
+    pub(crate) pending_stmt_close: Vec<StatementId>,
+
+    // where reading the response of the previous command stopped
+    pub(crate) state: ResponseState,
+
+    // maximum total size of the column values in a single row
+    pub(crate) max_row_bytes: usize,
+
+    // how bound decimals exceeding the scale of a `NUMERIC` parameter are handled
+    pub(crate) numeric_rounding: XuguNumericRounding,
+
+    // session settings negotiated at login, restored by `reset_session`
+    pub(crate) initial_session: SessionSettings,
+    // session settings currently in effect
+    pub(crate) session: SessionSettings,
+    // offset of the session time zone in seconds, used when decoding `DATETIME`
+    pub(crate) time_zone: Option<i32>,
 
     log_settings: LogSettings,
 }
@@ -58,78 +86,58 @@ impl XuguConnectionInner {
 impl XuguConnection {
     // will return when the connection is ready for another query
     pub(crate) async fn wait_until_ready(&mut self) -> Result<(), Error> {
+        self.check_broken()?;
+
         if !self.inner.stream.write_buffer_mut().is_empty() {
-            self.inner.stream.flush().await?;
+            if let Err(e) = self.inner.stream.flush().await {
+                self.inner.state = ResponseState::Broken;
+                return Err(e.into());
+            }
         }
 
-        let mut num_columns = self.inner.last_num_columns;
         while self.inner.pending_ready_for_query_count > 0 {
-            let message: ReceivedMessage = self.inner.stream.recv().await?;
-            let cnt = ServerContext::new(self.inner.stream.server_version);
-            match message.format {
-                BackendMessageFormat::ErrorResponse => {
-                    let err: ErrorResponse = message.decode(&mut self.inner.stream, cnt).await?;
+            match self.recv_response().await? {
+                Response::Error(err) => {
                     return Err(Error::Database(Box::new(XuguDatabaseError::from_str(
                         &err.error,
                     ))));
                 }
-                BackendMessageFormat::MessageResponse => {
-                    let notice: MessageResponse =
-                        message.decode(&mut self.inner.stream, cnt).await?;
-                    let (log_level, tracing_level) = (Level::Info, tracing::Level::INFO);
-                    let log_is_enabled = log::log_enabled!(
-                        target: "sqlx::xugu::notice",
-                        log_level
-                    ) || sqlx_core::private_tracing_dynamic_enabled!(
-                        target: "sqlx::xugu::notice",
-                        tracing_level
-                    );
-                    if log_is_enabled {
-                        sqlx_core::private_tracing_dynamic_event!(
-                            target: "sqlx::xugu::notice",
-                            tracing_level,
-                            message = notice.msg
-                        );
-                    }
-                }
-                BackendMessageFormat::RowDescription => {
-                    // 接收列数据
-                    let columns: RowDescription =
-                        message.decode(&mut self.inner.stream, cnt).await?;
-                    num_columns = columns.fields.len();
-                    self.inner.last_num_columns = num_columns;
-                }
-                BackendMessageFormat::DataRow => {
-                    // 接收行数据
-                    let _: DataRow = message.decode(&mut self.inner.stream, cnt).await?;
-                    for _ in 0..num_columns {
-                        let len = self.inner.stream.read_i32().await?;
-                        let _buf = self.inner.stream.read_bytes(len as usize).await?;
-                    }
-                }
-                BackendMessageFormat::ReadyForQuery => {
-                    let _: ReadyForQuery = message.decode(&mut self.inner.stream, cnt).await?;
+                Response::ReadyForQuery => {
                     self.handle_ready_for_query().await?;
                 }
-                BackendMessageFormat::InsertResponse => {
-                    let _: InsertResponse = message.decode(&mut self.inner.stream, cnt).await?;
-                }
-                BackendMessageFormat::DeleteResponse => {
-                    let _: DeleteResponse = message.decode(&mut self.inner.stream, cnt).await?;
-                }
-                BackendMessageFormat::UpdateResponse => {
-                    let _: UpdateResponse = message.decode(&mut self.inner.stream, cnt).await?;
-                }
-                BackendMessageFormat::ParameterDescription => {
-                    let _: ParameterDescription =
-                        message.decode(&mut self.inner.stream, cnt).await?;
-                }
+                _ => {}
             }
         }
 
         Ok(())
     }
 
+    /// 发送一个命令，等待关闭的预处理语句在同一次写入中随后发送
+    ///
+    /// 命令写入缓冲区后即计入 `pending_ready_for_query_count`，即使发送或读取响应时被取消，
+    /// 下一次 [`wait_until_ready`][Self::wait_until_ready] 也会读取并丢弃它的响应。
+    /// 关闭语句的应答排在该命令的响应之后，同样计入，不需要额外的往返。
+    pub(crate) async fn send_command<'en, T>(&mut self, payload: T) -> Result<(), Error>
+    where
+        T: ProtocolEncode<'en, ()>,
+    {
+        self.inner.stream.write_packet(payload)?;
+        self.inner.pending_ready_for_query_count += 1;
+        self.write_pending_stmt_close()?;
+        self.inner.stream.flush().await?;
+
+        Ok(())
+    }
+
+    fn write_pending_stmt_close(&mut self) -> Result<(), Error> {
+        for id in std::mem::take(&mut self.inner.pending_stmt_close) {
+            self.inner.stream.write_packet(StmtClose(id))?;
+            self.inner.pending_ready_for_query_count += 1;
+        }
+
+        Ok(())
+    }
+
     #[inline(always)]
     async fn handle_ready_for_query(&mut self) -> Result<(), Error> {
         self.inner.pending_ready_for_query_count = self
@@ -188,10 +196,14 @@ impl Connection for XuguConnection {
     fn ping(&mut self) -> BoxFuture<'_, Result<(), Error>> {
         Box::pin(async move {
             self.wait_until_ready().await?;
-            self.inner.stream.send_packet(Ping).await?;
-            let _ok: OkPacket = self.inner.stream.recv().await?;
+            self.send_command(Ping).await?;
 
-            Ok(())
+            // 成功或数据库错误时应答已读取完毕
+            let result = self.recv_ok().await;
+            if matches!(result, Ok(()) | Err(Error::Database(_))) {
+                self.handle_ready_for_query().await?;
+            }
+            result
         })
     }
 
@@ -220,16 +232,13 @@ impl Connection for XuguConnection {
         Box::pin(async move {
             self.wait_until_ready().await?;
 
-            while let Some((statement_id, _)) = self.inner.cache_statement.remove_lru() {
-                self.inner
-                    .stream
-                    .send_packet(StmtClose(statement_id))
-                    .await?;
-
-                let _ok: OkPacket = self.inner.stream.recv().await?;
+            while let Some(statement_id) = self.inner.cache_statement.remove_lru() {
+                self.inner.pending_stmt_close.push(statement_id);
             }
 
-            Ok(())
+            // 所有关闭命令一次发送，只等待一次
+            self.write_pending_stmt_close()?;
+            self.wait_until_ready().await
         })
     }
 
@@ -239,11 +248,105 @@ impl Connection for XuguConnection {
 
     #[doc(hidden)]
     fn flush(&mut self) -> BoxFuture<'_, Result<(), Error>> {
-        self.wait_until_ready().boxed()
+        Box::pin(async move {
+            self.write_pending_stmt_close()?;
+            self.wait_until_ready().await
+        })
     }
 
     #[doc(hidden)]
     fn should_flush(&self) -> bool {
-        !self.inner.stream.write_buffer().is_empty()
+        !self.inner.stream.write_buffer().is_empty() || !self.inner.pending_stmt_close.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::mock::{self, block_on, poll_until_blocked, Command, MockServer};
+    use crate::protocol::text::ColumnType;
+    use sqlx_core::connection::Connection;
+    use sqlx_core::executor::Executor;
+    use sqlx_core::row::Row;
+    use std::pin::pin;
+
+    const KEY: [u8; 32] = [7; 32];
+
+    /// `SELECT n` 返回一行整数 `n`，其他语句返回 `K`
+    fn handler(command: &Command) -> Vec<u8> {
+        match command.sql.strip_prefix("SELECT ") {
+            Some(n) => {
+                let n: i32 = n.parse().unwrap();
+                [
+                    mock::columns(&[("N", ColumnType::INTEGER)]),
+                    mock::row(&[&n.to_be_bytes()]),
+                    mock::ok(),
+                ]
+                .concat()
+            }
+            None => mock::ok(),
+        }
+    }
+
+    fn servers() -> [MockServer; 2] {
+        [MockServer::new(handler), MockServer::with_ssl(handler, KEY)]
+    }
+
+    fn select(conn: &mut super::XuguConnection, n: i32) -> i32 {
+        let rows = block_on(conn.fetch_all(&*format!("SELECT {n}"))).unwrap();
+        assert_eq!(rows.len(), 1);
+        rows[0].get(0)
+    }
+
+    #[test]
+    fn cancel_while_reading_response() {
+        let len = handler(&Command {
+            sql: "SELECT 1".into(),
+            params: Vec::new(),
+        })
+        .len();
+
+        // 在响应的每个字节处取消，覆盖所有消息边界
+        for cut in 0..len {
+            for server in servers() {
+                let mut conn = server.connect();
+                server.set_read_budget(Some(cut));
+                assert!(poll_until_blocked(pin!(conn.fetch_all("SELECT 1"))).is_none());
+
+                server.set_read_budget(None);
+                assert_eq!(select(&mut conn, 2), 2, "cancelled after {cut} bytes");
+                assert_eq!(server.unread(), 0);
+            }
+        }
+    }
+
+    #[test]
+    fn cancel_while_sending_command() {
+        // '?' + 长度 + "SELECT 1" + '\0' + 参数个数
+        let len = 1 + 4 + 8 + 1 + 4;
+
+        for budget in 0..len {
+            for server in servers() {
+                let mut conn = server.connect();
+                server.set_write_budget(Some(budget));
+                assert!(poll_until_blocked(pin!(conn.fetch_all("SELECT 1"))).is_none());
+
+                server.set_write_budget(None);
+                assert_eq!(select(&mut conn, 2), 2, "cancelled after {budget} bytes");
+                assert_eq!(server.sql(), ["SELECT 1", "SELECT 2"]);
+            }
+        }
+    }
+
+    #[test]
+    fn cancel_ping() {
+        for server in servers() {
+            let mut conn = server.connect();
+            server.set_read_budget(Some(0));
+            assert!(poll_until_blocked(pin!(conn.ping())).is_none());
+
+            server.set_read_budget(None);
+            assert_eq!(select(&mut conn, 3), 3);
+            block_on(conn.ping()).unwrap();
+        }
     }
 }
@@ -0,0 +1,182 @@
+use crate::io::AsyncStreamExt;
+use crate::protocol::message::*;
+use crate::protocol::text::OkPacket;
+use crate::protocol::ServerContext;
+use crate::XuguConnection;
+use log::Level;
+use sqlx_core::bytes::Bytes;
+use sqlx_core::{err_protocol, Error};
+
+/// 响应的读取状态
+///
+/// 读取消息的 future 或 stream 可能在任意 await 处被丢弃。未读完的消息由
+/// [`XuguStream`][super::stream::XuguStream] 记录已读取的数据，下次读取时从消息开头重放，
+/// 因此只需在这里记录消息之间的状态。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ResponseState {
+    /// 可以读取下一条消息，`num_columns` 为当前结果集的列数，用于读取 `DataRow`
+    Ready { num_columns: usize },
+    /// 读取消息时出错，流的位置已丢失，连接无法继续使用
+    Broken,
+}
+
+impl Default for ResponseState {
+    fn default() -> Self {
+        ResponseState::Ready { num_columns: 0 }
+    }
+}
+
+/// 一条完整读取的响应消息
+pub(crate) enum Response {
+    Error(ErrorResponse),
+    Notice(MessageResponse),
+    ReadyForQuery,
+    Insert(InsertResponse),
+    Delete(DeleteResponse),
+    Update(UpdateResponse),
+    RowDescription(RowDescription),
+    ParameterDescription(ParameterDescription),
+    DataRow(Vec<Bytes>),
+}
+
+impl XuguConnection {
+    pub(crate) fn check_broken(&self) -> Result<(), Error> {
+        match self.inner.state {
+            ResponseState::Ready { .. } => Ok(()),
+            ResponseState::Broken => Err(broken()),
+        }
+    }
+
+    /// 读取下一条完整的响应消息
+    ///
+    /// 本方法可以在任意 await 处取消，下次调用时重新读取同一条消息；
+    /// 读取出错时连接被标记为已损坏。
+    pub(crate) async fn recv_response(&mut self) -> Result<Response, Error> {
+        let ResponseState::Ready { num_columns } = self.inner.state else {
+            return Err(broken());
+        };
+
+        self.inner.stream.start_message();
+        let response = match self.read_response(num_columns).await {
+            Ok(response) => response,
+            Err(e) => {
+                self.inner.state = ResponseState::Broken;
+                return Err(e);
+            }
+        };
+        self.inner.stream.finish_message();
+
+        match &response {
+            Response::RowDescription(columns) => {
+                self.inner.state = ResponseState::Ready {
+                    num_columns: columns.fields.len(),
+                };
+            }
+            Response::Notice(notice) => log_notice(&notice.msg),
+            _ => {}
+        }
+
+        Ok(response)
+    }
+
+    async fn read_response(&mut self, num_columns: usize) -> Result<Response, Error> {
+        let stream = &mut self.inner.stream;
+        let message: ReceivedMessage = stream.recv().await?;
+        let cnt = ServerContext::new(stream.server_version);
+
+        let response = match message.format {
+            BackendMessageFormat::ErrorResponse => {
+                Response::Error(message.decode(stream, cnt).await?)
+            }
+            BackendMessageFormat::MessageResponse => {
+                Response::Notice(message.decode(stream, cnt).await?)
+            }
+            BackendMessageFormat::ReadyForQuery => {
+                let _: ReadyForQuery = message.decode(stream, cnt).await?;
+                Response::ReadyForQuery
+            }
+            BackendMessageFormat::InsertResponse => {
+                Response::Insert(message.decode(stream, cnt).await?)
+            }
+            BackendMessageFormat::DeleteResponse => {
+                Response::Delete(message.decode(stream, cnt).await?)
+            }
+            BackendMessageFormat::UpdateResponse => {
+                Response::Update(message.decode(stream, cnt).await?)
+            }
+            BackendMessageFormat::RowDescription => {
+                Response::RowDescription(message.decode(stream, cnt).await?)
+            }
+            BackendMessageFormat::ParameterDescription => {
+                Response::ParameterDescription(message.decode(stream, cnt).await?)
+            }
+            BackendMessageFormat::DataRow => {
+                // 接收行数据
+                let _: DataRow = message.decode(stream, cnt).await?;
+                let mut row = Vec::with_capacity(num_columns);
+                let mut row_bytes = 0usize;
+                for _ in 0..num_columns {
+                    let len = stream.read_len().await?;
+                    row_bytes = row_bytes.saturating_add(len);
+                    if row_bytes > self.inner.max_row_bytes {
+                        return Err(err_protocol!(
+                            "row of at least {} bytes exceeds max_row_bytes ({} bytes)",
+                            row_bytes,
+                            self.inner.max_row_bytes
+                        ));
+                    }
+                    row.push(stream.read_bytes(len).await?);
+                }
+                Response::DataRow(row)
+            }
+        };
+
+        Ok(response)
+    }
+
+    /// 读取一个 [`OkPacket`]，可以在任意 await 处取消
+    ///
+    /// 服务器返回的数据库错误不会使连接损坏。
+    pub(crate) async fn recv_ok(&mut self) -> Result<(), Error> {
+        self.check_broken()?;
+
+        self.inner.stream.start_message();
+        match self.inner.stream.recv::<OkPacket>().await {
+            Ok(_) => {
+                self.inner.stream.finish_message();
+                Ok(())
+            }
+            Err(e @ Error::Database(_)) => {
+                self.inner.stream.finish_message();
+                Err(e)
+            }
+            Err(e) => {
+                self.inner.state = ResponseState::Broken;
+                Err(e)
+            }
+        }
+    }
+}
+
+fn broken() -> Error {
+    err_protocol!("connection is broken: a previous response was only partially read")
+}
+
+/// 服务器返回的警告和信息
+fn log_notice(msg: &str) {
+    let (log_level, tracing_level) = (Level::Info, tracing::Level::INFO);
+    let log_is_enabled = log::log_enabled!(
+        target: "sqlx::xugu::notice",
+        log_level
+    ) || sqlx_core::private_tracing_dynamic_enabled!(
+        target: "sqlx::xugu::notice",
+        tracing_level
+    );
+    if log_is_enabled {
+        sqlx_core::private_tracing_dynamic_event!(
+            target: "sqlx::xugu::notice",
+            tracing_level,
+            message = msg
+        );
+    }
+}
@@ -0,0 +1,398 @@
+use crate::options::{check_compatible_mode, check_identity_mode, check_lob_ret};
+use crate::protocol::text::Query;
+use crate::{Xugu, XuguConnectOptions, XuguConnection};
+use futures_core::future::BoxFuture;
+use sqlx_core::executor::Executor;
+use sqlx_core::pool::{PoolConnectionMetadata, PoolOptions};
+use sqlx_core::Error;
+use std::sync::Arc;
+
+/// 登录时协商的会话参数，用于连接归还连接池时恢复会话状态
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct SessionSettings {
+    pub(crate) auto_commit: bool,
+    pub(crate) time_zone: Option<String>,
+    pub(crate) iso_level: Option<String>,
+    pub(crate) lock_timeout: Option<String>,
+    pub(crate) lob_ret: Option<String>,
+    pub(crate) identity_mode: Option<String>,
+    pub(crate) current_schema: Option<String>,
+    pub(crate) compatible_mode: Option<String>,
+    /// 建立连接时执行的初始化 SQL，重置会话后重新执行
+    pub(crate) init_sql: Vec<String>,
+}
+
+impl SessionSettings {
+    pub(crate) fn time_zone_offset(&self) -> Option<i32> {
+        self.time_zone.as_deref().and_then(parse_time_zone_offset)
+    }
+
+    pub(crate) fn from_options(options: &XuguConnectOptions) -> Self {
+        Self {
+            auto_commit: options.auto_commit,
+            time_zone: options.time_zone.clone(),
+            iso_level: options.iso_level.clone(),
+            lock_timeout: options.lock_timeout.clone(),
+            lob_ret: options.lob_ret.clone(),
+            identity_mode: options.identity_mode.clone(),
+            current_schema: options.current_schema.clone(),
+            compatible_mode: options.compatible_mode.clone(),
+            init_sql: options.init_sql.clone(),
+        }
+    }
+
+    /// 将会话参数恢复为 `self` 所需的 `SET` 语句
+    ///
+    /// 无论会话参数是否通过类型化的方法修改过，每个参数都会重新设置：
+    /// 用户也可能直接执行 `SET current_schema TO ...` 等语句，驱动无法得知。
+    /// 登录时未指定的参数恢复为服务器默认值。最后按顺序重新执行初始化 SQL，
+    /// 与建立连接时一致。
+    fn restore_statements(&self) -> Vec<String> {
+        let auto_commit = if self.auto_commit { "on" } else { "off" };
+        let mut statements = vec![set_sql("auto_commit", auto_commit)];
+
+        let params = [
+            ("time_zone", &self.time_zone),
+            ("iso_level", &self.iso_level),
+            ("lob_ret", &self.lob_ret),
+            ("identity_mode", &self.identity_mode),
+            ("current_schema", &self.current_schema),
+            ("compatible_mode", &self.compatible_mode),
+        ];
+        for (name, initial) in params {
+            match initial {
+                Some(value) => statements.push(set_sql(name, &quote(value))),
+                None => statements.push(set_sql(name, "DEFAULT")),
+            }
+        }
+        statements.push(set_sql(
+            "lock_timeout",
+            self.lock_timeout.as_deref().unwrap_or("DEFAULT"),
+        ));
+        statements.extend(self.init_sql.iter().cloned());
+
+        statements
+    }
+}
+
+/// 解析时区字符串，返回相对 UTC 的秒数
+///
+/// 支持 `GMT`、`UTC`、`GMT+08:00`、`GMT-8`、`+08:00`、`+0800` 等固定偏移格式；
+/// 地区名（如 `Asia/Shanghai`）无法解析，返回 `None`。
+pub(crate) fn parse_time_zone_offset(tz: &str) -> Option<i32> {
+    let tz = tz.trim();
+    let offset = tz
+        .strip_prefix("GMT")
+        .or_else(|| tz.strip_prefix("UTC"))
+        .unwrap_or(tz)
+        .trim();
+    if offset.is_empty() {
+        return Some(0);
+    }
+
+    let (sign, hm) = match offset.as_bytes()[0] {
+        b'+' => (1, &offset[1..]),
+        b'-' => (-1, &offset[1..]),
+        _ => return None,
+    };
+    let (hours, minutes) = match hm.split_once(':') {
+        Some((h, m)) => (h, m),
+        None if hm.len() == 4 => hm.split_at(2),
+        None => (hm, "0"),
+    };
+    let hours: i32 = hours.parse().ok()?;
+    let minutes: i32 = minutes.parse().ok()?;
+    if !(0..=14).contains(&hours) || !(0..60).contains(&minutes) {
+        return None;
+    }
+
+    Some(sign * (hours * 3600 + minutes * 60))
+}
+
+pub(crate) fn set_sql(name: &str, value: &str) -> String {
+    format!("SET {name} TO {value}")
+}
+
+pub(crate) fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+impl XuguConnection {
+    /// 重置会话状态
+    ///
+    /// 回滚未结束的事务，并将 `auto_commit`、`time_zone`、`current_schema` 等会话参数
+    /// 恢复为建立连接时 [`XuguConnectOptions`] 中的设置；连接选项中未指定的参数恢复为服务器默认值。
+    /// 即使参数是直接通过 `SET` 语句修改的，也会被恢复。
+    /// 恢复会话参数后重新执行 [`XuguConnectOptions::init_sql`] 中的语句。
+    ///
+    /// 会话级临时表中的数据随事务回滚或会话结束而清除，本方法不会删除临时表本身。
+    pub async fn reset_session(&mut self) -> Result<(), Error> {
+        self.wait_until_ready().await?;
+
+        let mut statements = vec![String::from("ROLLBACK")];
+        statements.extend(self.inner.initial_session.restore_statements());
+
+        // 流水线发送，只等待一次
+        for sql in &statements {
+            self.inner.stream.write_packet(Query(sql))?;
+            self.inner.pending_ready_for_query_count += 1;
+        }
+        self.inner.transaction_depth = 0;
+        self.inner.xa_xid = None;
+
+        self.wait_until_ready().await?;
+
+        self.inner.session = self.inner.initial_session.clone();
+        self.inner.time_zone = self.inner.session.time_zone_offset();
+
+        Ok(())
+    }
+
+    /// 设置当前会话的默认模式
+    pub async fn set_current_schema(&mut self, schema: &str) -> Result<(), Error> {
+        self.set_session_param("current_schema", &quote(schema))
+            .await?;
+        self.inner.session.current_schema = Some(schema.to_owned());
+
+        Ok(())
+    }
+
+    /// 设置当前会话的时区，如 `GMT+08:00`
+    ///
+    /// 同时更新解码不带时区的 `DATETIME` 时使用的时区。
+    pub async fn set_time_zone(&mut self, time_zone: &str) -> Result<(), Error> {
+        self.set_session_param("time_zone", &quote(time_zone))
+            .await?;
+        self.inner.session.time_zone = Some(time_zone.to_owned());
+        self.inner.time_zone = parse_time_zone_offset(time_zone);
+
+        Ok(())
+    }
+
+    /// 设置当前会话的兼容模式，如 `MYSQL`、`ORACLE`、`POSTGRESQL`、`NONE`
+    pub async fn set_compatible_mode(&mut self, mode: &str) -> Result<(), Error> {
+        self.set_session_param("compatible_mode", &quote(check_compatible_mode(mode)?))
+            .await?;
+        self.inner.session.compatible_mode = Some(mode.to_owned());
+
+        Ok(())
+    }
+
+    /// 设置当前会话的大对象返回方式
+    pub async fn set_lob_ret(&mut self, lob_ret: &str) -> Result<(), Error> {
+        self.set_session_param("lob_ret", &quote(check_lob_ret(lob_ret)?))
+            .await?;
+        self.inner.session.lob_ret = Some(lob_ret.to_owned());
+
+        Ok(())
+    }
+
+    /// 设置当前会话的自增长使用模式，如 `DEFAULT`、`NULL_AS_AUTO_INCREMENT`、`ZERO_AS_AUTO_INCREMENT`
+    pub async fn set_identity_mode(&mut self, mode: &str) -> Result<(), Error> {
+        self.set_session_param("identity_mode", &quote(check_identity_mode(mode)?))
+            .await?;
+        self.inner.session.identity_mode = Some(mode.to_owned());
+
+        Ok(())
+    }
+
+    /// 设置当前会话是否自动提交
+    pub async fn set_auto_commit(&mut self, auto_commit: bool) -> Result<(), Error> {
+        let value = if auto_commit { "on" } else { "off" };
+        self.set_session_param("auto_commit", value).await?;
+        self.inner.session.auto_commit = auto_commit;
+
+        Ok(())
+    }
+
+    async fn set_session_param(&mut self, name: &str, value: &str) -> Result<(), Error> {
+        self.execute(&*set_sql(name, value)).await?;
+
+        Ok(())
+    }
+}
+
+/// [`PoolOptions`] 的扩展方法
+pub trait XuguPoolOptionsExt {
+    /// 连接归还连接池时自动调用 [`XuguConnection::reset_session`]，
+    /// 避免上一次使用时修改的会话状态（模式、时区、未结束的事务等）泄露给下一个使用者。
+    ///
+    /// 重置失败的连接会被关闭，而不是放回连接池。
+    ///
+    /// 注意：[`PoolOptions`] 只保存一个 `after_release` 回调，且不提供读取已设置回调的方法，
+    /// 本方法会覆盖之前通过 [`PoolOptions::after_release`] 设置的回调；
+    /// 需要同时使用自定义回调时请改用 [`after_release_with_reset`][Self::after_release_with_reset]。
+    fn reset_session_on_release(self) -> Self;
+
+    /// 同 [`reset_session_on_release`][Self::reset_session_on_release]，
+    /// 重置成功后再调用 `callback`，用于代替 [`PoolOptions::after_release`]
+    ///
+    /// 重置失败时不调用 `callback`，直接关闭连接；
+    /// 否则由 `callback` 的返回值决定是否将连接放回连接池。
+    fn after_release_with_reset<F>(self, callback: F) -> Self
+    where
+        for<'c> F: Fn(&'c mut XuguConnection, PoolConnectionMetadata) -> BoxFuture<'c, Result<bool, Error>>
+            + 'static
+            + Send
+            + Sync;
+}
+
+impl XuguPoolOptionsExt for PoolOptions<Xugu> {
+    fn reset_session_on_release(self) -> Self {
+        self.after_release(|conn, _meta| -> BoxFuture<'_, Result<bool, Error>> {
+            Box::pin(async move { Ok(conn.reset_session().await.is_ok()) })
+        })
+    }
+
+    fn after_release_with_reset<F>(self, callback: F) -> Self
+    where
+        for<'c> F: Fn(&'c mut XuguConnection, PoolConnectionMetadata) -> BoxFuture<'c, Result<bool, Error>>
+            + 'static
+            + Send
+            + Sync,
+    {
+        let callback = Arc::new(callback);
+        self.after_release(move |conn, meta| -> BoxFuture<'_, Result<bool, Error>> {
+            let callback = Arc::clone(&callback);
+            Box::pin(reset_then(conn, move |conn| callback(conn, meta)))
+        })
+    }
+}
+
+/// 重置会话，成功后调用 `next`；重置失败的连接直接关闭，不再调用 `next`
+async fn reset_then<F>(conn: &mut XuguConnection, next: F) -> Result<bool, Error>
+where
+    F: for<'c> FnOnce(&'c mut XuguConnection) -> BoxFuture<'c, Result<bool, Error>>,
+{
+    if conn.reset_session().await.is_err() {
+        return Ok(false);
+    }
+    next(conn).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connection::mock::{self, block_on, MockServer};
+
+    #[test]
+    fn reset_session_restores_raw_set() {
+        let server = MockServer::new(|_| mock::ok());
+        let options = XuguConnectOptions::new().current_schema("app");
+        let mut conn = server.connect_with(&options);
+
+        block_on(async {
+            conn.execute("SET current_schema TO 'other'").await?;
+            conn.execute("SET time_zone TO 'GMT+03:00'").await?;
+            conn.reset_session().await
+        })
+        .unwrap();
+
+        assert_eq!(
+            server.sql()[2..],
+            [
+                "ROLLBACK",
+                "SET auto_commit TO on",
+                "SET time_zone TO DEFAULT",
+                "SET iso_level TO 'READ COMMITTED'",
+                "SET lob_ret TO DEFAULT",
+                "SET identity_mode TO DEFAULT",
+                "SET current_schema TO 'app'",
+                "SET compatible_mode TO DEFAULT",
+                "SET lock_timeout TO DEFAULT",
+            ]
+        );
+    }
+
+    #[test]
+    fn reset_session_after_setter() {
+        let server = MockServer::new(|_| mock::ok());
+        let options = XuguConnectOptions::new().timezone(String::from("GMT+08:00"));
+        let mut conn = server.connect_with(&options);
+
+        block_on(async {
+            conn.set_time_zone("GMT-05:00").await?;
+            assert_eq!(conn.inner.time_zone, Some(-5 * 3600));
+            conn.reset_session().await?;
+            // 每次重置都发送全部参数
+            conn.reset_session().await
+        })
+        .unwrap();
+
+        assert_eq!(conn.inner.time_zone, Some(8 * 3600));
+        let sql = server.sql();
+        assert_eq!(sql.len(), 1 + 2 * 9);
+        assert_eq!(sql[1..10], sql[10..]);
+        assert_eq!(sql[3], "SET time_zone TO 'GMT+08:00'");
+    }
+
+    #[test]
+    fn reset_session_reruns_init_sql() {
+        let server = MockServer::new(|_| mock::ok());
+        let options = XuguConnectOptions::new()
+            .init_sql("SET lock_timeout TO 10")
+            .init_sql("CREATE TEMP TABLE IF NOT EXISTS t(id INT)");
+        let mut conn = server.connect_with(&options);
+
+        block_on(conn.reset_session()).unwrap();
+
+        let sql = server.sql();
+        assert_eq!(sql[0], "ROLLBACK");
+        assert_eq!(sql[8], "SET lock_timeout TO DEFAULT");
+        assert_eq!(
+            sql[9..],
+            [
+                "SET lock_timeout TO 10",
+                "CREATE TEMP TABLE IF NOT EXISTS t(id INT)"
+            ]
+        );
+    }
+
+    #[test]
+    fn reset_then_chains_callback() {
+        let server = MockServer::new(|_| mock::ok());
+        let mut conn = server.connect_with(&XuguConnectOptions::new());
+
+        let keep = block_on(reset_then(
+            &mut conn,
+            |conn| -> BoxFuture<'_, Result<bool, Error>> {
+                Box::pin(async move {
+                    conn.execute("SELECT 1 FROM dual").await?;
+                    Ok(false)
+                })
+            },
+        ))
+        .unwrap();
+
+        // 先重置会话，再调用自定义回调，返回值由回调决定
+        assert!(!keep);
+        let sql = server.sql();
+        assert_eq!(sql[0], "ROLLBACK");
+        assert_eq!(sql.last().unwrap(), "SELECT 1 FROM dual");
+    }
+
+    #[test]
+    fn reset_then_skips_callback_on_failure() {
+        let server = MockServer::new(|command| {
+            if command.sql == "ROLLBACK" {
+                [mock::error("E1 connection broken"), mock::ok()].concat()
+            } else {
+                mock::ok()
+            }
+        });
+        let mut conn = server.connect_with(&XuguConnectOptions::new());
+
+        let mut called = false;
+        let keep = block_on(reset_then(
+            &mut conn,
+            |_| -> BoxFuture<'_, Result<bool, Error>> {
+                called = true;
+                Box::pin(async { Ok(true) })
+            },
+        ))
+        .unwrap();
+
+        assert!(!keep);
+        assert!(!called);
+    }
+}
@@ -1,13 +1,23 @@
 use self::rsa::rsa_encrypt;
 use turing_cipher::Turing;
 
+mod pin;
 mod rsa;
 
+pub use pin::server_key_fingerprint;
+pub(crate) use pin::ServerKeyCheck;
+
+/// 每次生成的密钥流长度
+const KEY_STREAM_LEN: usize = 340;
+
+/// 同一段密钥流循环使用的总字节数，之后重新生成密钥流（与服务器保持一致）
+const KEY_STREAM_PERIOD: usize = KEY_STREAM_LEN * 10;
+
 #[allow(dead_code)]
 pub struct TurningComputer {
     turing: Turing,
     pos: usize,
-    key_stream: [u8; 340],
+    key_stream: [u8; KEY_STREAM_LEN],
 }
 
 #[allow(dead_code)]
@@ -16,7 +26,7 @@ impl TurningComputer {
         TurningComputer {
             turing: Turing::new(),
             pos: 0,
-            key_stream: [0; 340],
+            key_stream: [0; KEY_STREAM_LEN],
         }
     }
 
@@ -27,11 +37,24 @@ impl TurningComputer {
         self.pos = 0;
     }
 
+    /// 按密钥流的连续片段批量异或，每个片段内按 8 字节一组处理
     pub fn xor_buff(&mut self, buf: &mut [u8], size: usize) {
-        for i in 0..buf.len().min(size) {
-            buf[i] ^= self.key_stream[self.pos % 340];
-            self.pos += 1;
-            if self.pos == 3400 {
+        let len = buf.len().min(size);
+        let mut buf = &mut buf[..len];
+
+        while !buf.is_empty() {
+            let offset = self.pos % KEY_STREAM_LEN;
+            let n = buf
+                .len()
+                .min(KEY_STREAM_LEN - offset)
+                .min(KEY_STREAM_PERIOD - self.pos);
+
+            let (head, rest) = buf.split_at_mut(n);
+            xor_slice(head, &self.key_stream[offset..offset + n]);
+            buf = rest;
+
+            self.pos += n;
+            if self.pos == KEY_STREAM_PERIOD {
                 self.turing.turing_gen(&mut self.key_stream).unwrap();
                 self.pos = 0;
             }
@@ -91,6 +114,27 @@ impl TurningComputer {
     }
 }
 
+/// `dst ^= key`，两者长度相同
+#[inline]
+fn xor_slice(dst: &mut [u8], key: &[u8]) {
+    debug_assert_eq!(dst.len(), key.len());
+
+    let mut dst_words = dst.chunks_exact_mut(8);
+    let mut key_words = key.chunks_exact(8);
+    for (d, k) in (&mut dst_words).zip(&mut key_words) {
+        let x =
+            u64::from_ne_bytes(d.try_into().unwrap()) ^ u64::from_ne_bytes(k.try_into().unwrap());
+        d.copy_from_slice(&x.to_ne_bytes());
+    }
+    for (d, k) in dst_words
+        .into_remainder()
+        .iter_mut()
+        .zip(key_words.remainder())
+    {
+        *d ^= k;
+    }
+}
+
 /// 每30字节插入两个0x07字符
 fn insert7(data: &[u8]) -> Vec<u8> {
     // 计算完整块数量和总容量
@@ -131,3 +175,123 @@ pub fn init_key(read: &mut TurningComputer, send: &mut TurningComputer, key: &[u
     read.init(key, &iv);
     send.init(key, &iv);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 逐字节异或的参考实现
+    struct Reference(TurningComputer);
+
+    impl Reference {
+        fn xor_buff(&mut self, buf: &mut [u8]) {
+            let c = &mut self.0;
+            for b in buf {
+                *b ^= c.key_stream[c.pos % KEY_STREAM_LEN];
+                c.pos += 1;
+                if c.pos == KEY_STREAM_PERIOD {
+                    c.turing.turing_gen(&mut c.key_stream).unwrap();
+                    c.pos = 0;
+                }
+            }
+        }
+    }
+
+    fn key() -> [u8; 32] {
+        std::array::from_fn(|i| i as u8)
+    }
+
+    fn computer() -> TurningComputer {
+        let mut computer = TurningComputer::new();
+        computer.init(&key(), &[]);
+        computer
+    }
+
+    #[test]
+    fn key_stream_regression() {
+        // 回归测试：以下数据取自当前实现的输出，没有独立来源，只用于发现实现的意外改动；
+        // 与服务器的互通性需要在真实服务器上验证。
+        // 明文全为 0 时密文即密钥流：340 字节处回到同一段密钥流的开头，3400 字节处重新生成
+        let mut buf = vec![0u8; KEY_STREAM_PERIOD * 2 + 16];
+        computer().xor_buff_exact(&mut buf);
+
+        let known: [(usize, [u8; 8]); 4] = [
+            (0, [0x38, 0xcb, 0xd9, 0x85, 0x52, 0x34, 0x9d, 0x10]),
+            (336, [0x19, 0x13, 0x8f, 0xe4, 0x38, 0xcb, 0xd9, 0x85]),
+            (3396, [0x19, 0x13, 0x8f, 0xe4, 0xc8, 0x8c, 0x94, 0x4a]),
+            (6796, [0xfd, 0xf4, 0x7b, 0xc9, 0xef, 0x17, 0xfb, 0xac]),
+        ];
+        for (at, expected) in known {
+            assert_eq!(buf[at..at + 8], expected, "key stream at {at}");
+        }
+    }
+
+    #[test]
+    fn matches_byte_by_byte() {
+        let plain: Vec<u8> = (0..KEY_STREAM_PERIOD * 3 + 123)
+            .map(|i| (i * 31 % 251) as u8)
+            .collect();
+        let mut expected = plain.clone();
+        Reference(computer()).xor_buff(&mut expected);
+
+        // 各种分段方式，覆盖 8 字节分组、340 字节片段和 3400 字节周期的边界
+        let chunkings: [&[usize]; 8] = [
+            &[1],
+            &[7],
+            &[8],
+            &[339, 1, 1],
+            &[340],
+            &[341],
+            &[3399, 2],
+            &[3400, 3401, 5, 333],
+        ];
+        for sizes in chunkings {
+            let mut computer = computer();
+            let mut actual = plain.clone();
+            let mut rest = actual.as_mut_slice();
+            for &size in sizes.iter().cycle() {
+                if rest.is_empty() {
+                    break;
+                }
+                let (head, tail) = rest.split_at_mut(size.min(rest.len()));
+                computer.xor_buff_exact(head);
+                rest = tail;
+            }
+            assert!(actual == expected, "chunk sizes {sizes:?}");
+
+            // 加密两次还原明文
+            let mut computer = self::computer();
+            computer.xor_buff_exact(&mut actual);
+            assert!(actual == plain, "chunk sizes {sizes:?}");
+        }
+    }
+
+    #[test]
+    fn xor_buff_limits_size() {
+        let mut a = [0xffu8; 16];
+        let mut b = [0xffu8; 10];
+        computer().xor_buff(&mut a, 10);
+        computer().xor_buff_exact(&mut b);
+        assert_eq!(a[..10], b);
+        assert_eq!(a[10..], [0xff; 6]);
+    }
+
+    #[test]
+    fn xor_slice_unaligned() {
+        let key: Vec<u8> = (0..40).collect();
+        for start in 0..8 {
+            for len in 0..(32 - start) {
+                let mut dst = [0x5au8; 40];
+                xor_slice(&mut dst[start..start + len], &key[start..start + len]);
+                for (i, b) in dst.iter().enumerate() {
+                    let expected = if (start..start + len).contains(&i) {
+                        0x5a ^ key[i]
+                    } else {
+                        0x5a
+                    };
+                    assert_eq!(*b, expected);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,219 @@
+use crate::XuguConnectOptions;
+use sha2::{Digest, Sha256};
+use sqlx_core::Error;
+use std::fs::OpenOptions;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+
+/// 服务器密钥指纹：`public_key` 与 `trail_key` 拼接后的 SHA-256，小写十六进制
+pub fn server_key_fingerprint(public_key: &[u8], trail_key: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(public_key);
+    hasher.update(trail_key);
+
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// 规范化指纹：去掉 `sha256:` 前缀、`:` 分隔符和空白，转为小写
+fn normalize_fingerprint(value: &str) -> Result<String, Error> {
+    let value = value.trim();
+    let value = value.strip_prefix("sha256:").unwrap_or(value);
+    let fingerprint: String = value
+        .chars()
+        .filter(|c| *c != ':' && !c.is_whitespace())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+
+    if fingerprint.len() != 64 || !fingerprint.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(Error::Configuration(
+            format!("invalid server key fingerprint {value:?}, expected 64 hex digits (SHA-256)")
+                .into(),
+        ));
+    }
+
+    Ok(fingerprint)
+}
+
+/// 加密握手时对服务器密钥的校验
+pub(crate) struct ServerKeyCheck {
+    /// 服务器地址 `host:port`，用作 `known_hosts` 文件中的键
+    address: String,
+    fingerprint: Option<String>,
+    known_hosts: Option<PathBuf>,
+}
+
+/// 同一进程内的连接依次检查和追加 `known_hosts` 文件，
+/// 并发连接同一个新服务器时只记录一次
+static KNOWN_HOSTS_LOCK: Mutex<()> = Mutex::new(());
+
+impl ServerKeyCheck {
+    pub(crate) fn from_options(options: &XuguConnectOptions) -> Self {
+        Self {
+            address: format!("{}:{}", options.host, options.port),
+            fingerprint: options.ssl_server_fingerprint.clone(),
+            known_hosts: options.ssl_known_hosts.clone(),
+        }
+    }
+
+    /// 校验服务器密钥的选项只在加密连接时生效，未开启 `use_ssl` 时返回错误，
+    /// 以免误以为连接受到了保护
+    pub(crate) fn check_options(options: &XuguConnectOptions) -> Result<(), Error> {
+        if options.use_ssl {
+            return Ok(());
+        }
+
+        let option = if options.ssl_server_fingerprint.is_some() {
+            "ssl_server_fingerprint"
+        } else if options.ssl_known_hosts.is_some() {
+            "ssl_known_hosts"
+        } else {
+            return Ok(());
+        };
+        Err(Error::Configuration(
+            format!("{option} requires use_ssl to be enabled").into(),
+        ))
+    }
+
+    /// 校验服务器密钥，必须在发送加密的登录信息之前调用
+    pub(crate) async fn verify(&self, public_key: &[u8], trail_key: &[u8]) -> Result<(), Error> {
+        let actual = server_key_fingerprint(public_key, trail_key);
+
+        if let Some(expected) = &self.fingerprint {
+            if normalize_fingerprint(expected)? != actual {
+                return Err(mismatch(&self.address, &actual));
+            }
+        }
+
+        if let Some(path) = &self.known_hosts {
+            let path = path.clone();
+            let address = self.address.clone();
+            // 文件读写在阻塞线程池中执行，不阻塞异步运行时
+            sqlx_core::rt::spawn_blocking(move || check_known_hosts(&path, &address, &actual))
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// 在 `known_hosts` 文件中查找服务器的指纹，没有记录时追加
+fn check_known_hosts(path: &Path, address: &str, actual: &str) -> Result<(), Error> {
+    // 持有锁期间读取并追加，其他连接不会在两者之间写入同一个服务器
+    let _guard = KNOWN_HOSTS_LOCK
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e.into()),
+    };
+
+    let known = content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once(char::is_whitespace))
+        .find(|(known_address, _)| *known_address == address);
+
+    match known {
+        Some((_, expected)) => {
+            if normalize_fingerprint(expected)? != actual {
+                return Err(mismatch(address, actual));
+            }
+        }
+        // 首次连接，信任并记录服务器密钥
+        None => {
+            let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+            if !content.is_empty() && !content.ends_with('\n') {
+                writeln!(file)?;
+            }
+            writeln!(file, "{address} {actual}")?;
+        }
+    }
+
+    Ok(())
+}
+
+fn mismatch(address: &str, actual: &str) -> Error {
+    Error::Tls(
+        format!(
+            "server key fingerprint for {address} does not match the pinned fingerprint (got {actual})"
+        )
+        .into(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn temp_file(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "sqlx-xugu-{name}-{}-known_hosts",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn options_require_ssl() {
+        let options = XuguConnectOptions::new();
+        assert!(ServerKeyCheck::check_options(&options).is_ok());
+
+        let fingerprint = "ab".repeat(32);
+        for options in [
+            options.clone().ssl_server_fingerprint(&fingerprint),
+            options.clone().ssl_known_hosts("known_hosts"),
+        ] {
+            let err = ServerKeyCheck::check_options(&options).unwrap_err();
+            assert!(matches!(err, Error::Configuration(_)), "{err:?}");
+            assert!(ServerKeyCheck::check_options(&options.use_ssl(true)).is_ok());
+        }
+    }
+
+    #[test]
+    fn known_hosts_trust_on_first_use() {
+        let path = temp_file("tofu");
+        let a = server_key_fingerprint(b"a", b"1");
+        let b = server_key_fingerprint(b"b", b"2");
+
+        check_known_hosts(&path, "db:5138", &a).unwrap();
+        check_known_hosts(&path, "db:5138", &a).unwrap();
+        let err = check_known_hosts(&path, "db:5138", &b).unwrap_err();
+        assert!(matches!(err, Error::Tls(_)), "{err:?}");
+        check_known_hosts(&path, "db2:5138", &b).unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(content, format!("db:5138 {a}\ndb2:5138 {b}\n"));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn known_hosts_concurrent_first_use() {
+        let path = Arc::new(temp_file("concurrent"));
+        let fingerprint = Arc::new(server_key_fingerprint(b"a", b"1"));
+
+        let threads: Vec<_> = (0..8)
+            .map(|_| {
+                let path = Arc::clone(&path);
+                let fingerprint = Arc::clone(&fingerprint);
+                std::thread::spawn(move || check_known_hosts(&path, "db:5138", &fingerprint))
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap().unwrap();
+        }
+
+        let content = std::fs::read_to_string(&*path).unwrap();
+        assert_eq!(content.lines().count(), 1, "{content}");
+        std::fs::remove_file(&*path).unwrap();
+    }
+}
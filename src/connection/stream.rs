@@ -1,4 +1,4 @@
-use super::ssl::{encrypt_conn, init_key, TurningComputer};
+use super::ssl::{encrypt_conn, init_key, ServerKeyCheck, TurningComputer};
 use crate::io::{AsyncStreamExt, StreamDecode};
 use crate::protocol::ServerContext;
 use crate::{XuguConnectOptions, XuguDatabaseError};
@@ -15,6 +15,17 @@ pub struct XuguStream {
     turing_read: TurningComputer,
     turing_send: TurningComputer,
     use_ssl: bool,
+    /// 加密握手时对服务器密钥的校验
+    server_key_check: ServerKeyCheck,
+    /// 单次读取的最大字节数
+    max_packet_size: usize,
+    /// 是否正在读取一条消息
+    in_message: bool,
+    /// 当前消息已读取的数据，每次读取一项；
+    /// 读取被取消（future 被丢弃）后，再次读取该消息时先按顺序重放这些数据
+    replay: Vec<Bytes>,
+    /// 下一次读取在 `replay` 中的位置
+    replay_pos: usize,
     /// 服务器协议版本号（201老协议，301新协议）
     pub(crate) server_version: i16,
 }
@@ -41,25 +52,74 @@ impl AsyncStreamExt for XuguStream {
     }
 
     async fn read_bytes(&mut self, len: usize) -> Result<Bytes> {
-        let mut buf = self.socket.read_buffered(len).await?;
-        if self.use_ssl {
-            self.turing_read.xor_buff_exact(buf.as_mut());
+        if len > self.max_packet_size {
+            return Err(err_protocol!(
+                "packet of {} bytes exceeds max_packet_size ({} bytes)",
+                len,
+                self.max_packet_size
+            ));
         }
-        Ok(buf.freeze())
+        if !self.in_message {
+            return self.read_socket(len).await;
+        }
+
+        if let Some(buf) = self.replay.get(self.replay_pos) {
+            if buf.len() != len {
+                return Err(err_protocol!(
+                    "replayed read of {} bytes does not match the requested {} bytes",
+                    buf.len(),
+                    len
+                ));
+            }
+            self.replay_pos += 1;
+            return Ok(buf.clone());
+        }
+
+        let buf = self.read_socket(len).await?;
+        self.replay.push(buf.clone());
+        self.replay_pos += 1;
+        Ok(buf)
     }
 
     async fn read_str(&mut self) -> Result<String> {
         let len = self.read_i32().await?;
-        if len <= 0 {
+        let len = usize::try_from(len)
+            .map_err(|_| err_protocol!("invalid string length {} in server response", len))?;
+        if len == 0 {
             return Ok(String::new());
         }
-        let bytes = self.read_bytes(len as usize).await?;
+        let bytes = self.read_bytes(len).await?;
 
         Ok(String::from_utf8_lossy(trim_nul_end(&bytes)).into_owned())
     }
 }
 
 impl XuguStream {
+    /// `read_buffered` 在数据到齐前不会消耗缓冲区，解密也在数据到齐后同步完成，
+    /// 因此本方法被取消时不会丢失数据
+    async fn read_socket(&mut self, len: usize) -> Result<Bytes> {
+        let mut buf = self.socket.read_buffered(len).await?;
+        if self.use_ssl {
+            self.turing_read.xor_buff_exact(buf.as_mut());
+        }
+        Ok(buf.freeze())
+    }
+
+    /// 开始读取一条消息
+    ///
+    /// 如果上一次读取的消息未完成，从该消息的开头重放已读取的数据。
+    pub(crate) fn start_message(&mut self) {
+        self.in_message = true;
+        self.replay_pos = 0;
+    }
+
+    /// 消息已完整读取
+    pub(crate) fn finish_message(&mut self) {
+        self.in_message = false;
+        self.replay.clear();
+        self.replay_pos = 0;
+    }
+
     /// 读取缓冲区已到达的剩余数据
     async fn read_buf(&mut self) -> Result<Bytes> {
         self.socket
@@ -89,17 +149,34 @@ fn trim_nul_end(mut bytes: &[u8]) -> &[u8] {
 
 impl XuguStream {
     pub(super) async fn connect(options: &XuguConnectOptions) -> Result<Self> {
+        ServerKeyCheck::check_options(options)?;
+
         let host = options.host.as_str();
         let port = options.port;
         let socket = connect_tcp(host, port, SocketIntoBox).await?;
 
-        Ok(Self {
+        Ok(Self::new(socket, options))
+    }
+
+    pub(super) fn new(socket: Box<dyn Socket>, options: &XuguConnectOptions) -> Self {
+        Self {
             socket: BufferedSocket::new(socket),
             turing_read: TurningComputer::new(),
             turing_send: TurningComputer::new(),
             use_ssl: options.use_ssl,
+            server_key_check: ServerKeyCheck::from_options(options),
+            max_packet_size: options.max_packet_size,
+            in_message: false,
+            replay: Vec::new(),
+            replay_pos: 0,
             server_version: 201,
-        })
+        }
+    }
+
+    /// 跳过加密握手，直接使用给定的会话密钥
+    #[cfg(test)]
+    pub(super) fn init_ssl_key(&mut self, key: &[u8; 32]) {
+        init_key(&mut self.turing_read, &mut self.turing_send, key);
     }
 
     pub(super) async fn do_handshake(&mut self, conn_str: &str, opts_version: i16) -> Result<bool> {
@@ -112,13 +189,6 @@ impl XuguStream {
         self.handshake_recv(opts_version).await
     }
 
-    pub(crate) fn before_flush(&mut self) {
-        if self.use_ssl {
-            let buf = self.socket.write_buffer_mut().get_mut();
-            self.turing_send.xor_buff_exact(buf);
-        }
-    }
-
     async fn handshake(&mut self, conn_str: &str) -> Result<()> {
         self.socket.write(conn_str.as_bytes())?;
         self.socket.flush().await?;
@@ -130,6 +200,9 @@ impl XuguStream {
         self.socket.flush().await?;
         let public_key: Bytes = self.socket.read(32).await?;
         let trail_key: Bytes = self.socket.read(32).await?;
+        self.server_key_check
+            .verify(&public_key, &trail_key)
+            .await?;
 
         let mut key = [0u8; 32];
         getrandom::fill(&mut key).unwrap();
@@ -175,16 +248,24 @@ impl XuguStream {
         T: ProtocolEncode<'en, ()>,
     {
         self.write_packet(payload)?;
-        self.before_flush();
         self.socket.flush().await?;
         Ok(())
     }
 
+    /// 将消息写入写缓冲区，启用加密时立即加密新写入的数据
+    ///
+    /// 每个字节只在写入时加密一次，`flush` 被取消后再次发送缓冲区中剩余的数据时不会重复加密。
     pub(crate) fn write_packet<'en, T>(&mut self, payload: T) -> Result<()>
     where
         T: ProtocolEncode<'en, ()>,
     {
-        self.socket.write_with(payload, ())
+        let start = self.socket.write_buffer().get().len();
+        self.socket.write_with(payload, ())?;
+        if self.use_ssl {
+            let buf = &mut self.socket.write_buffer_mut().get_mut()[start..];
+            self.turing_send.xor_buff_exact(buf);
+        }
+        Ok(())
     }
 
     pub(crate) async fn recv<T>(&mut self) -> Result<T>
@@ -208,3 +289,28 @@ impl DerefMut for XuguStream {
         &mut self.socket
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::connection::mock::{self, block_on, MockServer};
+    use sqlx_core::executor::Executor;
+    use sqlx_core::Error;
+
+    #[test]
+    fn read_str_rejects_negative_length() {
+        let server = MockServer::new(|_| [&b"E"[..], &(-1i32).to_be_bytes()].concat());
+        let mut conn = server.connect();
+
+        let err = block_on(conn.execute("SELECT 1")).unwrap_err();
+        assert!(matches!(err, Error::Protocol(_)), "{err:?}");
+    }
+
+    #[test]
+    fn read_str_empty() {
+        let server = MockServer::new(|_| [mock::error(""), mock::ok()].concat());
+        let mut conn = server.connect();
+
+        let err = block_on(conn.execute("SELECT 1")).unwrap_err();
+        assert!(matches!(err, Error::Database(_)), "{err:?}");
+    }
+}
@@ -0,0 +1,460 @@
+use crate::{Xugu, XuguConnection, XuguRow};
+use futures_core::future::BoxFuture;
+use sqlx_core::executor::Executor;
+use sqlx_core::row::Row;
+use sqlx_core::transaction::Transaction;
+use sqlx_core::{err_protocol, Error};
+use std::fmt::{self, Display, Formatter};
+
+/// 分布式事务（XA）的全局事务标识
+///
+/// 由 格式ID `format_id`、全局事务ID `gtrid` 和 分支限定符 `bqual` 组成，
+/// 对应 XA 规范中的 XID 结构。
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct XuguXid {
+    pub format_id: i32,
+    pub gtrid: String,
+    pub bqual: String,
+}
+
+impl XuguXid {
+    /// 使用全局事务ID创建 XID，分支限定符为空，格式ID为 `1`
+    pub fn new(gtrid: impl Into<String>) -> Self {
+        Self {
+            format_id: 1,
+            gtrid: gtrid.into(),
+            bqual: String::new(),
+        }
+    }
+
+    /// 设置分支限定符
+    pub fn bqual(mut self, bqual: impl Into<String>) -> Self {
+        self.bqual = bqual.into();
+        self
+    }
+
+    /// 设置格式ID
+    pub fn format_id(mut self, format_id: i32) -> Self {
+        self.format_id = format_id;
+        self
+    }
+}
+
+/// 按 XA 语句的语法输出：`'gtrid','bqual',format_id`
+impl Display for XuguXid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "'{}','{}',{}",
+            self.gtrid.replace('\'', "''"),
+            self.bqual.replace('\'', "''"),
+            self.format_id
+        )
+    }
+}
+
+impl XuguConnection {
+    /// 开启一个 XA 分支事务 `XA START`
+    ///
+    /// 返回的事务只能通过 [`prepare_transaction`][Self::prepare_transaction] 进入准备阶段，
+    /// 或者直接回滚；不支持嵌套。直接提交会返回错误；回滚或未提交就丢弃时，
+    /// 依次发送 `XA END` 和 `XA ROLLBACK` 结束该分支。
+    pub fn begin_xa<'c>(
+        &'c mut self,
+        xid: &XuguXid,
+    ) -> BoxFuture<'c, Result<Transaction<'c, Xugu>, Error>> {
+        let xid = xid.clone();
+        Box::pin(async move {
+            let statement = format!("XA START {xid}");
+            let mut tx: Transaction<'c, Xugu> =
+                Transaction::begin(self, Some(statement.into())).await?;
+            tx.inner.xa_xid = Some(xid);
+
+            Ok(tx)
+        })
+    }
+
+    /// 两阶段提交的第一阶段：结束并准备当前的 XA 分支事务
+    ///
+    /// 必须在 [`begin_xa`][Self::begin_xa] 使用同一个 `xid` 开启的事务中调用，且不能存在保存点。
+    /// 准备成功后，事务与当前会话分离，事务深度归零，
+    /// 之后需使用 [`commit_prepared`][Self::commit_prepared] 或
+    /// [`rollback_prepared`][Self::rollback_prepared] 完成第二阶段。
+    pub async fn prepare_transaction(&mut self, xid: &XuguXid) -> Result<(), Error> {
+        match &self.inner.xa_xid {
+            Some(started) if started == xid => {}
+            Some(started) => {
+                return Err(Error::InvalidArgument(format!(
+                    "XA PREPARE: the open XA transaction was started with xid ({started}), not ({xid})"
+                )));
+            }
+            None => {
+                return Err(Error::InvalidArgument(format!(
+                    "XA PREPARE: no XA transaction was started with begin_xa for xid ({xid})"
+                )));
+            }
+        }
+
+        let depth = self.inner.transaction_depth;
+        if depth != 1 {
+            return Err(Error::InvalidArgument(format!(
+                "XA PREPARE requires a single open transaction, but transaction depth is {depth}"
+            )));
+        }
+
+        self.execute(&*format!("XA END {xid}")).await?;
+        self.execute(&*format!("XA PREPARE {xid}")).await?;
+        self.inner.transaction_depth = 0;
+        self.inner.xa_xid = None;
+
+        Ok(())
+    }
+
+    /// 两阶段提交的第二阶段：提交已准备的 XA 事务
+    ///
+    /// 可以在任意连接上调用，但当前连接不能处于事务中。
+    pub async fn commit_prepared(&mut self, xid: &XuguXid) -> Result<(), Error> {
+        self.ensure_no_transaction("XA COMMIT")?;
+        self.execute(&*format!("XA COMMIT {xid}")).await?;
+
+        Ok(())
+    }
+
+    /// 两阶段提交的第二阶段：回滚已准备的 XA 事务
+    ///
+    /// 可以在任意连接上调用，但当前连接不能处于事务中。
+    pub async fn rollback_prepared(&mut self, xid: &XuguXid) -> Result<(), Error> {
+        self.ensure_no_transaction("XA ROLLBACK")?;
+        self.execute(&*format!("XA ROLLBACK {xid}")).await?;
+
+        Ok(())
+    }
+
+    /// 列出服务器上处于准备状态（in-doubt）的 XA 事务
+    ///
+    /// 按列名读取 `XA RECOVER` 的结果：`formatID`、`gtrid_length`、`bqual_length` 和 `data`，
+    /// 即 MySQL 兼容的结果格式，`data` 为 `gtrid` 与 `bqual` 的拼接。
+    /// 该格式未在所有虚谷版本上验证，结果缺少这些列时返回协议错误。
+    pub async fn recover(&mut self) -> Result<Vec<XuguXid>, Error> {
+        let rows = self.fetch_all("XA RECOVER").await?;
+
+        rows.iter().map(parse_recover_row).collect()
+    }
+
+    fn ensure_no_transaction(&self, cmd: &str) -> Result<(), Error> {
+        let depth = self.inner.transaction_depth;
+        if depth > 0 {
+            return Err(Error::InvalidArgument(format!(
+                "{cmd} cannot be executed inside a transaction (transaction depth is {depth})"
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// 解析 `XA RECOVER` 的一行
+fn parse_recover_row(row: &XuguRow) -> Result<XuguXid, Error> {
+    let column = |names: &[&str]| {
+        names
+            .iter()
+            .find_map(|name| row.column_names.get(*name).copied())
+            .ok_or_else(|| {
+                let found: Vec<_> = row.columns().iter().map(|c| c.name.to_string()).collect();
+                err_protocol!(
+                    "XA RECOVER: missing column {} (got {})",
+                    names[0],
+                    found.join(", ")
+                )
+            })
+    };
+
+    let format_id: i32 = row.try_get(column(&["formatid", "format_id"])?)?;
+    let gtrid_length: i32 = row.try_get(column(&["gtrid_length"])?)?;
+    let bqual_length: i32 = row.try_get(column(&["bqual_length"])?)?;
+    let data: Vec<u8> = row.try_get(column(&["data"])?)?;
+
+    let lengths = usize::try_from(gtrid_length)
+        .ok()
+        .zip(usize::try_from(bqual_length).ok())
+        .filter(|(gtrid, bqual)| gtrid.checked_add(*bqual) == Some(data.len()));
+    let Some((gtrid_length, _)) = lengths else {
+        return Err(err_protocol!(
+            "XA RECOVER: gtrid length {} and bqual length {} do not match data of {} bytes",
+            gtrid_length,
+            bqual_length,
+            data.len()
+        ));
+    };
+
+    let (gtrid, bqual) = data.split_at(gtrid_length);
+    let text = |part: &[u8]| {
+        String::from_utf8(part.to_vec())
+            .map_err(|_| err_protocol!("XA RECOVER: xid is not valid UTF-8: {:?}", data))
+    };
+
+    Ok(XuguXid {
+        format_id,
+        gtrid: text(gtrid)?,
+        bqual: text(bqual)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connection::mock::{self, block_on, MockServer};
+    use crate::protocol::text::ColumnType;
+    use sqlx_core::connection::Connection;
+
+    fn server() -> MockServer {
+        MockServer::new(|command| match command.sql.as_str() {
+            "XA RECOVER" => [
+                mock::columns(&[
+                    ("formatID", ColumnType::INTEGER),
+                    ("gtrid_length", ColumnType::INTEGER),
+                    ("bqual_length", ColumnType::INTEGER),
+                    ("data", ColumnType::CHAR),
+                ]),
+                mock::row(&[
+                    &7i32.to_be_bytes(),
+                    &3i32.to_be_bytes(),
+                    &2i32.to_be_bytes(),
+                    b"g-1b1",
+                ]),
+                mock::row(&[
+                    &1i32.to_be_bytes(),
+                    &4i32.to_be_bytes(),
+                    &0i32.to_be_bytes(),
+                    b"tx'2",
+                ]),
+                mock::ok(),
+            ]
+            .concat(),
+            _ => mock::ok(),
+        })
+    }
+
+    #[test]
+    fn two_phase_commit() {
+        let server = server();
+        let mut conn = server.connect();
+        let xid = XuguXid::new("g-1").bqual("b1").format_id(7);
+
+        block_on(async {
+            let mut tx = conn.begin_xa(&xid).await?;
+            tx.execute("INSERT INTO t VALUES(1)").await?;
+            tx.prepare_transaction(&xid).await?;
+            drop(tx);
+            assert_eq!(conn.inner.transaction_depth, 0);
+
+            conn.commit_prepared(&xid).await
+        })
+        .unwrap();
+
+        assert_eq!(
+            server.sql(),
+            [
+                "XA START 'g-1','b1',7",
+                "INSERT INTO t VALUES(1)",
+                "XA END 'g-1','b1',7",
+                "XA PREPARE 'g-1','b1',7",
+                "XA COMMIT 'g-1','b1',7",
+            ]
+        );
+    }
+
+    #[test]
+    fn prepare_requires_begin_xa() {
+        let server = server();
+        let mut conn = server.connect();
+        let xid = XuguXid::new("g-1");
+
+        let err = block_on(async {
+            let mut tx = conn.begin().await?;
+            tx.prepare_transaction(&xid).await
+        })
+        .unwrap_err();
+        assert!(matches!(err, Error::InvalidArgument(_)), "{err}");
+
+        let err = block_on(async {
+            let mut tx = conn.begin_xa(&xid).await?;
+            tx.prepare_transaction(&XuguXid::new("g-2")).await
+        })
+        .unwrap_err();
+        assert!(matches!(err, Error::InvalidArgument(_)), "{err}");
+
+        assert!(!server.sql().iter().any(|sql| sql.starts_with("XA PREPARE")));
+    }
+
+    #[test]
+    fn prepare_after_rollback_is_rejected() {
+        let server = server();
+        let mut conn = server.connect();
+        let xid = XuguXid::new("g-1");
+
+        let err = block_on(async {
+            conn.begin_xa(&xid).await?.rollback().await?;
+            conn.prepare_transaction(&xid).await
+        })
+        .unwrap_err();
+        assert!(matches!(err, Error::InvalidArgument(_)), "{err}");
+    }
+
+    #[test]
+    fn rollback_ends_the_branch() {
+        let server = server();
+        let mut conn = server.connect();
+        let xid = XuguXid::new("g-1");
+
+        block_on(async {
+            let mut tx = conn.begin_xa(&xid).await?;
+            let mut nested = tx.begin().await?;
+            nested.execute("INSERT INTO t VALUES(1)").await?;
+            nested.rollback().await?;
+            tx.rollback().await
+        })
+        .unwrap();
+
+        assert_eq!(
+            server.sql(),
+            [
+                "XA START 'g-1','',1",
+                "SAVEPOINT _sqlx_savepoint_1",
+                "INSERT INTO t VALUES(1)",
+                "ROLLBACK TO SAVEPOINT _sqlx_savepoint_1",
+                "XA END 'g-1','',1",
+                "XA ROLLBACK 'g-1','',1",
+            ]
+        );
+        assert_eq!(conn.inner.xa_xid, None);
+    }
+
+    #[test]
+    fn drop_ends_the_branch() {
+        let server = server();
+        let mut conn = server.connect();
+        let xid = XuguXid::new("g-1");
+
+        block_on(async {
+            let mut tx = conn.begin_xa(&xid).await?;
+            tx.execute("INSERT INTO t VALUES(1)").await?;
+            drop(tx);
+            conn.ping().await
+        })
+        .unwrap();
+
+        assert_eq!(
+            server.sql()[2..4],
+            ["XA END 'g-1','',1", "XA ROLLBACK 'g-1','',1"]
+        );
+        assert_eq!(conn.inner.transaction_depth, 0);
+        assert_eq!(conn.inner.xa_xid, None);
+        assert_eq!(conn.inner.pending_ready_for_query_count, 0);
+    }
+
+    #[test]
+    fn plain_commit_is_rejected() {
+        let server = server();
+        let mut conn = server.connect();
+        let xid = XuguXid::new("g-1");
+
+        let err = block_on(async {
+            let tx = conn.begin_xa(&xid).await?;
+            tx.commit().await
+        })
+        .unwrap_err();
+        assert!(matches!(err, Error::InvalidArgument(_)), "{err}");
+
+        // 提交失败的事务被丢弃，随后回滚
+        block_on(conn.ping()).unwrap();
+        assert_eq!(
+            server.sql()[..3],
+            [
+                "XA START 'g-1','',1",
+                "XA END 'g-1','',1",
+                "XA ROLLBACK 'g-1','',1",
+            ]
+        );
+        assert!(!server.sql().iter().any(|sql| sql == "COMMIT"));
+    }
+
+    #[test]
+    fn misuse_is_an_invalid_argument() {
+        let server = server();
+        let mut conn = server.connect();
+        let xid = XuguXid::new("g-1");
+
+        let err = block_on(async {
+            let mut tx = conn.begin_xa(&xid).await?;
+            let mut nested = tx.begin().await?;
+            nested.prepare_transaction(&xid).await
+        })
+        .unwrap_err();
+        assert!(matches!(err, Error::InvalidArgument(_)), "{err}");
+
+        let err = block_on(async {
+            let mut tx = conn.begin().await?;
+            tx.commit_prepared(&xid).await
+        })
+        .unwrap_err();
+        assert!(matches!(err, Error::InvalidArgument(_)), "{err}");
+
+        let err = block_on(async {
+            let mut tx = conn.begin().await?;
+            tx.rollback_prepared(&xid).await
+        })
+        .unwrap_err();
+        assert!(matches!(err, Error::InvalidArgument(_)), "{err}");
+    }
+
+    #[test]
+    fn recover_splits_data_by_length() {
+        let server = server();
+        let mut conn = server.connect();
+
+        let xids = block_on(conn.recover()).unwrap();
+        assert_eq!(
+            xids,
+            [
+                XuguXid::new("g-1").bqual("b1").format_id(7),
+                XuguXid::new("tx'2"),
+            ]
+        );
+    }
+
+    #[test]
+    fn recover_rejects_unexpected_results() {
+        let server = MockServer::new(|_| {
+            [
+                mock::columns(&[
+                    ("formatID", ColumnType::INTEGER),
+                    ("gtrid_length", ColumnType::INTEGER),
+                    ("bqual_length", ColumnType::INTEGER),
+                    ("data", ColumnType::CHAR),
+                ]),
+                mock::row(&[
+                    &1i32.to_be_bytes(),
+                    &3i32.to_be_bytes(),
+                    &3i32.to_be_bytes(),
+                    b"abc",
+                ]),
+                mock::ok(),
+            ]
+            .concat()
+        });
+        let err = block_on(server.connect().recover()).unwrap_err();
+        assert!(matches!(err, Error::Protocol(_)), "{err}");
+
+        let server = MockServer::new(|_| {
+            [
+                mock::columns(&[("xid", ColumnType::CHAR)]),
+                mock::row(&[b"abc"]),
+                mock::ok(),
+            ]
+            .concat()
+        });
+        let err = block_on(server.connect().recover()).unwrap_err();
+        assert!(err.to_string().contains("missing column formatid"), "{err}");
+    }
+}
@@ -1,8 +1,11 @@
 use bytes::Bytes;
-use sqlx_core::Error;
+use sqlx_core::{err_protocol, Error};
 
 type Result<T> = std::result::Result<T, Error>;
 
+/// 按服务器返回的数量预分配内存的上限，避免恶意的数量字段导致巨大的内存分配
+pub(crate) const MAX_PREALLOC: usize = 1024;
+
 #[allow(dead_code)]
 pub trait AsyncStreamExt {
     async fn read_u8(&mut self) -> Result<u8>;
@@ -11,4 +14,16 @@ pub trait AsyncStreamExt {
     async fn read_i64(&mut self) -> Result<i64>;
     async fn read_bytes(&mut self, len: usize) -> Result<Bytes>;
     async fn read_str(&mut self) -> Result<String>;
+
+    /// 读取 `i32` 长度字段，负数视为协议错误
+    async fn read_len(&mut self) -> Result<usize> {
+        let len = self.read_i32().await?;
+        usize::try_from(len).map_err(|_| err_protocol!("invalid length field: {}", len))
+    }
+
+    /// 读取 `i32` 数量字段，负数视为协议错误
+    async fn read_count(&mut self) -> Result<usize> {
+        let num = self.read_i32().await?;
+        usize::try_from(num).map_err(|_| err_protocol!("invalid count field: {}", num))
+    }
 }
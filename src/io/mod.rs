@@ -2,4 +2,4 @@ mod decode;
 mod io_ext;
 
 pub(crate) use decode::StreamDecode;
-pub(crate) use io_ext::AsyncStreamExt;
+pub(crate) use io_ext::{AsyncStreamExt, MAX_PREALLOC};
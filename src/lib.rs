@@ -20,10 +20,13 @@ pub mod any;
 
 pub use arguments::XuguArguments;
 pub use column::XuguColumn;
-pub use connection::XuguConnection;
+pub use connection::{
+    server_key_fingerprint, XuguCachedStatement, XuguConnection, XuguPoolOptionsExt,
+    XuguStatementCacheStats, XuguXid,
+};
 pub use database::Xugu;
 pub use error::XuguDatabaseError;
-pub use options::XuguConnectOptions;
+pub use options::{XuguConnectOptions, XuguNumericRounding, XuguPasswordProvider};
 pub use query_result::XuguQueryResult;
 pub use row::XuguRow;
 use sqlx_core::executor::Executor;
@@ -37,6 +40,10 @@ pub use transaction::XuguTransactionManager;
 pub use type_info::XuguTypeInfo;
 pub use value::{XuguValue, XuguValueRef};
 
+// 供 `xugu_record!` 等宏使用，调用方不需要直接依赖 sqlx-core
+#[doc(hidden)]
+pub use sqlx_core as __sqlx_core;
+
 /// An alias for [`Pool`][sqlx_core::pool::Pool], specialized for Xugu.
 pub type XuguPool = pool::Pool<Xugu>;
 
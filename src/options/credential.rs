@@ -0,0 +1,48 @@
+use sqlx_core::error::BoxDynError;
+use sqlx_core::Error;
+use std::fmt::{self, Debug, Formatter};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// 密码提供者，每次建立连接时重新获取密码，以便连接池使用轮换后的密码
+#[derive(Clone)]
+pub enum XuguPasswordProvider {
+    /// 从环境变量读取
+    Env(String),
+    /// 从文件读取，忽略末尾的换行符
+    File(PathBuf),
+    /// 自定义回调
+    Callback(Arc<dyn Fn() -> Result<String, BoxDynError> + Send + Sync>),
+}
+
+impl XuguPasswordProvider {
+    pub(crate) async fn password(&self) -> Result<String, Error> {
+        match self {
+            XuguPasswordProvider::Env(var) => std::env::var(var).map_err(|e| {
+                Error::Configuration(
+                    format!("failed to read password from environment variable {var}: {e}").into(),
+                )
+            }),
+            // 在阻塞线程池中读取，不阻塞异步运行时
+            XuguPasswordProvider::File(path) => sqlx_core::fs::read_to_string(path)
+                .await
+                .map(|s| s.trim_end_matches(['\r', '\n']).to_owned())
+                .map_err(|e| {
+                    Error::Configuration(
+                        format!("failed to read password from file {}: {e}", path.display()).into(),
+                    )
+                }),
+            XuguPasswordProvider::Callback(callback) => callback().map_err(Error::Configuration),
+        }
+    }
+}
+
+impl Debug for XuguPasswordProvider {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            XuguPasswordProvider::Env(var) => f.debug_tuple("Env").field(var).finish(),
+            XuguPasswordProvider::File(path) => f.debug_tuple("File").field(path).finish(),
+            XuguPasswordProvider::Callback(_) => f.write_str("Callback(..)"),
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use sqlx_core::Error;
+
+/// 事务隔离级别 `iso_level` 的可选值
+const ISO_LEVELS: &[&str] = &[
+    "READ UNCOMMITTED",
+    "READ COMMITTED",
+    "REPEATABLE READ",
+    "SERIALIZABLE",
+];
+
+/// 大对象返回方式 `lob_ret` 的可选值
+const LOB_RETS: &[&str] = &["DESCRIPTOR", "DIRECT"];
+
+/// 自增长使用模式 `identity_mode` 的可选值
+const IDENTITY_MODES: &[&str] = &[
+    "DEFAULT",
+    "NULL_AS_AUTO_INCREMENT",
+    "ZERO_AS_AUTO_INCREMENT",
+];
+
+/// 兼容模式 `compatible_mode` 的可选值
+const COMPATIBLE_MODES: &[&str] = &["NONE", "MYSQL", "ORACLE", "POSTGRESQL"];
+
+/// 将登录参数值转为单引号字符串，单引号按 SQL 规则转义为两个单引号
+///
+/// 登录命令以 `\0` 结尾，值中包含 `\0` 时返回错误。
+pub(crate) fn quote_login_value(name: &str, value: &str) -> Result<String, Error> {
+    if value.contains('\0') {
+        return Err(Error::Configuration(
+            format!("login option `{name}` must not contain a NUL character").into(),
+        ));
+    }
+
+    Ok(format!("'{}'", value.replace('\'', "''")))
+}
+
+fn check_one_of<'a>(name: &str, value: &str, allowed: &[&'a str]) -> Result<&'a str, Error> {
+    let normalized = value.split_whitespace().collect::<Vec<_>>().join(" ");
+    allowed
+        .iter()
+        .find(|x| x.eq_ignore_ascii_case(&normalized))
+        .copied()
+        .ok_or_else(|| {
+            Error::Configuration(
+                format!(
+                    "invalid value {value:?} for `{name}`, expected one of: {}",
+                    allowed.join(", ")
+                )
+                .into(),
+            )
+        })
+}
+
+pub(crate) fn check_iso_level(value: &str) -> Result<&'static str, Error> {
+    check_one_of("iso_level", value, ISO_LEVELS)
+}
+
+pub(crate) fn check_lob_ret(value: &str) -> Result<&'static str, Error> {
+    check_one_of("lob_ret", value, LOB_RETS)
+}
+
+pub(crate) fn check_identity_mode(value: &str) -> Result<&'static str, Error> {
+    check_one_of("identity_mode", value, IDENTITY_MODES)
+}
+
+pub(crate) fn check_compatible_mode(value: &str) -> Result<&'static str, Error> {
+    check_one_of("compatible_mode", value, COMPATIBLE_MODES)
+}
+
+/// 字符集名只允许字母、数字、`-` 和 `_`
+pub(crate) fn check_charset(value: &str) -> Result<&str, Error> {
+    if value.is_empty()
+        || !value
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+    {
+        return Err(Error::Configuration(
+            format!("invalid value {value:?} for `charset`").into(),
+        ));
+    }
+
+    Ok(value)
+}
+
+/// 最大加锁等候时间，单位：毫秒
+pub(crate) fn check_lock_timeout(value: &str) -> Result<u32, Error> {
+    value.trim().parse().map_err(|_| {
+        Error::Configuration(
+            format!("invalid value {value:?} for `lock_timeout`, expected an integer").into(),
+        )
+    })
+}
+
+/// 关键字串以逗号分隔，每个关键字只允许字母、数字和 `_`
+pub(crate) fn check_keyword_filter(value: &str) -> Result<&str, Error> {
+    let valid = value.split(',').all(|keyword| {
+        let keyword = keyword.trim();
+        !keyword.is_empty()
+            && keyword
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'_')
+    });
+    if !valid {
+        return Err(Error::Configuration(
+            format!("invalid value {value:?} for `keyword_filter`").into(),
+        ));
+    }
+
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quote_doubles_single_quotes() {
+        let cases = [
+            ("", "''"),
+            ("abc", "'abc'"),
+            ("o'brien", "'o''brien'"),
+            ("''", "''''''"),
+            ("x' OR '1'='1", "'x'' OR ''1''=''1'"),
+            ("中文\\", "'中文\\'"),
+        ];
+        for (value, quoted) in cases {
+            assert_eq!(quote_login_value("user", value).unwrap(), quoted);
+        }
+    }
+
+    #[test]
+    fn quote_rejects_nul() {
+        for value in ["\0", "a\0b", "abc\0"] {
+            let err = quote_login_value("password", value).unwrap_err();
+            assert!(matches!(err, Error::Configuration(_)), "{err}");
+            assert!(err.to_string().contains("`password`"), "{err}");
+        }
+    }
+
+    #[test]
+    fn one_of_validators() {
+        assert_eq!(check_iso_level("read committed").unwrap(), "READ COMMITTED");
+        assert_eq!(
+            check_iso_level("\tRepeatable \n Read ").unwrap(),
+            "REPEATABLE READ"
+        );
+        assert_eq!(check_lob_ret("direct").unwrap(), "DIRECT");
+        assert_eq!(
+            check_identity_mode("null_as_auto_increment").unwrap(),
+            "NULL_AS_AUTO_INCREMENT"
+        );
+        assert_eq!(check_compatible_mode("PostgreSQL").unwrap(), "POSTGRESQL");
+
+        for value in [
+            "",
+            "READCOMMITTED",
+            "READ COMMITTED'",
+            "READ_COMMITTED",
+            "SNAPSHOT",
+        ] {
+            assert!(check_iso_level(value).is_err(), "{value:?}");
+        }
+        for value in ["", "DESCRIPTORS", "DIRECT;"] {
+            assert!(check_lob_ret(value).is_err(), "{value:?}");
+        }
+        for value in ["", "NULL AS AUTO INCREMENT", "DEFAULT'"] {
+            assert!(check_identity_mode(value).is_err(), "{value:?}");
+        }
+        for value in ["", "SQLSERVER", "MYSQL'--"] {
+            assert!(check_compatible_mode(value).is_err(), "{value:?}");
+        }
+
+        let err = check_lob_ret("x").unwrap_err().to_string();
+        assert!(err.contains("expected one of: DESCRIPTOR, DIRECT"), "{err}");
+    }
+
+    #[test]
+    fn charset() {
+        for value in ["UTF8", "gbk", "GB18030", "utf-8", "ISO_8859_1"] {
+            assert_eq!(check_charset(value).unwrap(), value);
+        }
+        for value in ["", " utf8", "utf8 ", "utf8'", "utf8;", "utf.8", "中文"] {
+            assert!(check_charset(value).is_err(), "{value:?}");
+        }
+    }
+
+    #[test]
+    fn lock_timeout() {
+        assert_eq!(check_lock_timeout("0").unwrap(), 0);
+        assert_eq!(check_lock_timeout(" 1000 ").unwrap(), 1000);
+        assert_eq!(check_lock_timeout("4294967295").unwrap(), u32::MAX);
+        for value in ["", "-1", "4294967296", "1.5", "10ms", "1 0"] {
+            assert!(check_lock_timeout(value).is_err(), "{value:?}");
+        }
+    }
+
+    #[test]
+    fn keyword_filter() {
+        for value in ["LIMIT", "limit,offset", "a_1, B2 ,c"] {
+            assert_eq!(check_keyword_filter(value).unwrap(), value);
+        }
+        for value in ["", ",", "a,,b", "a,", "a b", "a;b", "a'", "a-b"] {
+            assert!(check_keyword_filter(value).is_err(), "{value:?}");
+        }
+    }
+}
@@ -1,34 +1,105 @@
 mod connect;
+mod credential;
+mod login;
+mod numeric;
 mod parse;
 
+pub use credential::XuguPasswordProvider;
+pub(crate) use login::{
+    check_charset, check_compatible_mode, check_identity_mode, check_iso_level,
+    check_keyword_filter, check_lob_ret, check_lock_timeout, quote_login_value,
+};
+pub use numeric::XuguNumericRounding;
 use sqlx_core::connection::LogSettings;
+use sqlx_core::error::BoxDynError;
+use sqlx_core::Error;
+use std::fmt::{self, Debug, Formatter};
+use std::path::PathBuf;
+use std::sync::Arc;
 
-#[derive(Debug, Clone)]
+/// 单次读取的默认最大字节数：1 GiB
+const DEFAULT_MAX_PACKET_SIZE: usize = 1 << 30;
+
+/// 单行数据的默认最大字节数：1 GiB
+const DEFAULT_MAX_ROW_BYTES: usize = 1 << 30;
+
+#[derive(Clone)]
 pub struct XuguConnectOptions {
     pub(crate) host: String,
     pub(crate) port: u16,
     pub(crate) user: String,
     pub(crate) password: String,
+    pub(crate) password_provider: Option<XuguPasswordProvider>,
     pub(crate) database: String,
     pub(crate) charset: String,
     version: Option<i16>,
     return_schema: bool,
     return_rowid: bool,
     encryptor: Option<String>,
-    time_zone: Option<String>,
-    iso_level: Option<String>,
-    lock_timeout: Option<String>,
-    lob_ret: Option<String>,
-    identity_mode: Option<String>,
+    pub(crate) time_zone: Option<String>,
+    pub(crate) iso_level: Option<String>,
+    pub(crate) lock_timeout: Option<String>,
+    pub(crate) lob_ret: Option<String>,
+    pub(crate) identity_mode: Option<String>,
     keyword_filter: Option<String>,
     disable_binlog: Option<String>,
-    auto_commit: bool,
+    pub(crate) auto_commit: bool,
     pub(crate) use_ssl: bool,
-    current_schema: Option<String>,
-    compatible_mode: Option<String>,
+    pub(crate) ssl_server_fingerprint: Option<String>,
+    pub(crate) ssl_known_hosts: Option<PathBuf>,
+    pub(crate) current_schema: Option<String>,
+    pub(crate) compatible_mode: Option<String>,
+    pub(crate) application_name: Option<String>,
+    pub(crate) client_info: Option<String>,
+    pub(crate) init_sql: Vec<String>,
 
     pub(crate) log_settings: LogSettings,
     pub(crate) statement_cache_capacity: usize,
+    pub(crate) max_packet_size: usize,
+    pub(crate) max_row_bytes: usize,
+    pub(crate) numeric_rounding: XuguNumericRounding,
+}
+
+/// 密码和数据库解密密钥不会被输出
+impl Debug for XuguConnectOptions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        const REDACTED: &str = "<redacted>";
+
+        f.debug_struct("XuguConnectOptions")
+            .field("host", &self.host)
+            .field("port", &self.port)
+            .field("user", &self.user)
+            .field("password", &REDACTED)
+            .field("password_provider", &self.password_provider)
+            .field("database", &self.database)
+            .field("charset", &self.charset)
+            .field("version", &self.version)
+            .field("return_schema", &self.return_schema)
+            .field("return_rowid", &self.return_rowid)
+            .field("encryptor", &self.encryptor.as_ref().map(|_| REDACTED))
+            .field("time_zone", &self.time_zone)
+            .field("iso_level", &self.iso_level)
+            .field("lock_timeout", &self.lock_timeout)
+            .field("lob_ret", &self.lob_ret)
+            .field("identity_mode", &self.identity_mode)
+            .field("keyword_filter", &self.keyword_filter)
+            .field("disable_binlog", &self.disable_binlog)
+            .field("auto_commit", &self.auto_commit)
+            .field("use_ssl", &self.use_ssl)
+            .field("ssl_server_fingerprint", &self.ssl_server_fingerprint)
+            .field("ssl_known_hosts", &self.ssl_known_hosts)
+            .field("current_schema", &self.current_schema)
+            .field("compatible_mode", &self.compatible_mode)
+            .field("application_name", &self.application_name)
+            .field("client_info", &self.client_info)
+            .field("init_sql", &self.init_sql)
+            .field("log_settings", &self.log_settings)
+            .field("statement_cache_capacity", &self.statement_cache_capacity)
+            .field("max_packet_size", &self.max_packet_size)
+            .field("max_row_bytes", &self.max_row_bytes)
+            .field("numeric_rounding", &self.numeric_rounding)
+            .finish()
+    }
 }
 
 impl Default for XuguConnectOptions {
@@ -38,13 +109,21 @@ impl Default for XuguConnectOptions {
 }
 
 impl XuguConnectOptions {
-    pub(crate) fn to_conn_str(&self) -> String {
+    /// 使用本次连接的密码生成登录命令
+    ///
+    /// 所有字符串参数值均加引号并转义，枚举型参数按可选值校验，
+    /// 非法参数返回 [`Error::Configuration`]。
+    pub(crate) fn to_conn_str(&self, password: &str) -> Result<String, Error> {
         let return_schema = if self.return_schema { "on" } else { "off" };
         let version = self.get_version();
         // 必要参数
         let mut conn_str = format!(
-            "login database='{}' user='{}' password='{}' version={} return_schema={} return_cursor_id=on",
-            self.database, self.user, self.password, version, return_schema,
+            "login database={} user={} password={} version={} return_schema={} return_cursor_id=on",
+            quote_login_value("database", &self.database)?,
+            quote_login_value("user", &self.user)?,
+            quote_login_value("password", password)?,
+            version,
+            return_schema,
         );
 
         // 可选参数
@@ -53,44 +132,44 @@ impl XuguConnectOptions {
         }
         if let Some(encryptor) = &self.encryptor {
             conn_str += " encryptor=";
-            conn_str += encryptor;
+            conn_str += &quote_login_value("encryptor", encryptor)?;
         }
         if self.charset.is_empty() {
             conn_str += " char_set=utf8";
         } else {
-            conn_str = conn_str + " char_set=" + self.charset.as_str();
+            conn_str = conn_str + " char_set=" + check_charset(&self.charset)?;
         }
         if let Some(time_zone) = &self.time_zone {
-            conn_str = conn_str + " time_zone='" + time_zone + "'";
+            conn_str += " time_zone=";
+            conn_str += &quote_login_value("time_zone", time_zone)?;
         }
         if let Some(iso_level) = &self.iso_level {
             conn_str += " iso_level='";
-            conn_str += iso_level;
+            conn_str += check_iso_level(iso_level)?;
             conn_str += "'";
         }
         if let Some(lock_timeout) = &self.lock_timeout {
             conn_str += " lock_timeout=";
-            conn_str += lock_timeout;
+            conn_str += &check_lock_timeout(lock_timeout)?.to_string();
         }
         if let Some(lob_ret) = &self.lob_ret {
             conn_str += " lob_ret='";
-            conn_str += lob_ret;
+            conn_str += check_lob_ret(lob_ret)?;
             conn_str += "'";
         }
         if let Some(identity_mode) = &self.identity_mode {
             conn_str += " identity_mode='";
-            conn_str += identity_mode;
+            conn_str += check_identity_mode(identity_mode)?;
             conn_str += "'";
         }
         if let Some(keyword_filter) = &self.keyword_filter {
             conn_str += " keyword_filter='";
-            conn_str += keyword_filter;
+            conn_str += check_keyword_filter(keyword_filter)?;
             conn_str += "'";
         }
         if let Some(disable_binlog) = &self.disable_binlog {
-            conn_str += " disable_binlog='";
-            conn_str += disable_binlog;
-            conn_str += "'";
+            conn_str += " disable_binlog=";
+            conn_str += &quote_login_value("disable_binlog", disable_binlog)?;
         }
         if self.auto_commit {
             conn_str += " auto_commit=on";
@@ -98,18 +177,25 @@ impl XuguConnectOptions {
             conn_str += " auto_commit=off";
         }
         if let Some(current_schema) = &self.current_schema {
-            conn_str += " current_schema='";
-            conn_str += current_schema;
-            conn_str += "'";
+            conn_str += " current_schema=";
+            conn_str += &quote_login_value("current_schema", current_schema)?;
         }
         if let Some(compatible_mode) = &self.compatible_mode {
             conn_str += " compatible_mode='";
-            conn_str += compatible_mode;
+            conn_str += check_compatible_mode(compatible_mode)?;
             conn_str += "'";
         }
 
         conn_str += "\0";
-        conn_str
+        Ok(conn_str)
+    }
+
+    /// 获取本次连接使用的密码，设置了密码提供者时优先使用提供者
+    pub(crate) async fn resolve_password(&self) -> Result<String, Error> {
+        match &self.password_provider {
+            Some(provider) => provider.password().await,
+            None => Ok(self.password.clone()),
+        }
     }
 
     pub fn get_version(&self) -> i16 {
@@ -126,6 +212,7 @@ impl XuguConnectOptions {
             database: String::from("SYSTEM"),
             user: String::from("SYSDBA"),
             password: String::from("SYSDBA"),
+            password_provider: None,
             version: None,
             return_schema: true,
             return_rowid: false,
@@ -140,12 +227,20 @@ impl XuguConnectOptions {
             disable_binlog: None,
             auto_commit: true,
             use_ssl: false,
+            ssl_server_fingerprint: None,
+            ssl_known_hosts: None,
             current_schema: None,
             compatible_mode: None,
+            application_name: None,
+            client_info: None,
+            init_sql: Vec::new(),
 
             log_settings: Default::default(),
 
             statement_cache_capacity: 100,
+            max_packet_size: DEFAULT_MAX_PACKET_SIZE,
+            max_row_bytes: DEFAULT_MAX_ROW_BYTES,
+            numeric_rounding: XuguNumericRounding::Error,
         }
     }
 
@@ -179,11 +274,38 @@ impl XuguConnectOptions {
     }
 
     /// Sets the password to connect with.
+    ///
+    /// 会清除之前设置的密码提供者。
     pub fn password(mut self, password: &str) -> Self {
         self.password = password.to_owned();
+        self.password_provider = None;
         self
     }
 
+    /// 设置密码提供者，每次建立连接时重新获取密码
+    pub fn password_provider(mut self, provider: XuguPasswordProvider) -> Self {
+        self.password_provider = Some(provider);
+        self
+    }
+
+    /// 每次建立连接时从环境变量 `var` 读取密码
+    pub fn password_from_env(self, var: &str) -> Self {
+        self.password_provider(XuguPasswordProvider::Env(var.to_owned()))
+    }
+
+    /// 每次建立连接时从文件读取密码，忽略末尾的换行符
+    pub fn password_from_file(self, path: impl Into<PathBuf>) -> Self {
+        self.password_provider(XuguPasswordProvider::File(path.into()))
+    }
+
+    /// 每次建立连接时调用 `callback` 获取密码
+    pub fn password_with<F>(self, callback: F) -> Self
+    where
+        F: Fn() -> Result<String, BoxDynError> + Send + Sync + 'static,
+    {
+        self.password_provider(XuguPasswordProvider::Callback(Arc::new(callback)))
+    }
+
     /// Sets the version to connect with.
     pub fn version(mut self, version: i16) -> Self {
         self.version = Some(version);
@@ -196,7 +318,7 @@ impl XuguConnectOptions {
     }
 
     pub fn return_rowid(mut self, value: bool) -> Self {
-        self.return_schema = value;
+        self.return_rowid = value;
         self
     }
 
@@ -245,6 +367,26 @@ impl XuguConnectOptions {
         self
     }
 
+    /// 固定服务器密钥的 SHA-256 指纹，加密握手时服务器密钥不匹配则连接失败，
+    /// 避免登录信息被中间人截获
+    ///
+    /// 指纹为 64 位十六进制数，可以带 `sha256:` 前缀或用 `:` 分隔。
+    /// 需要同时开启 `use_ssl`，否则建立连接时返回 [`Error::Configuration`]。
+    pub fn ssl_server_fingerprint(mut self, fingerprint: &str) -> Self {
+        self.ssl_server_fingerprint = Some(fingerprint.into());
+        self
+    }
+
+    /// 首次信任（TOFU）模式：首次连接某个服务器时把其密钥指纹记录到文件中，
+    /// 之后的连接要求服务器密钥与记录的指纹一致
+    ///
+    /// 文件每行为 `host:port fingerprint`。同一进程内的并发连接依次读写该文件，不会重复记录。
+    /// 需要同时开启 `use_ssl`，否则建立连接时返回 [`Error::Configuration`]。
+    pub fn ssl_known_hosts(mut self, path: impl Into<PathBuf>) -> Self {
+        self.ssl_known_hosts = Some(path.into());
+        self
+    }
+
     pub fn current_schema(mut self, value: &str) -> Self {
         self.current_schema = Some(value.into());
         self
@@ -255,6 +397,38 @@ impl XuguConnectOptions {
         self
     }
 
+    /// 设置应用程序名，建立连接后写入会话信息，便于 DBA 识别连接来源
+    ///
+    /// 默认不发送。设置后在登录成功时执行 `SET application_name TO '...'`；
+    /// 服务器版本不支持该会话参数时忽略错误，不影响建立连接。
+    pub fn application_name(mut self, value: &str) -> Self {
+        self.application_name = Some(value.into());
+        self
+    }
+
+    /// 设置客户端信息（如客户端主机名、进程号），建立连接后写入会话信息
+    ///
+    /// 与 [`application_name`][Self::application_name] 相同，默认不发送，服务器不支持时忽略错误。
+    ///
+    /// ```rust
+    /// # use sqlx_xugu::XuguConnectOptions;
+    /// let options = XuguConnectOptions::new()
+    ///     .application_name("order-service")
+    ///     .client_info(&format!("pid={}", std::process::id()));
+    /// ```
+    pub fn client_info(mut self, value: &str) -> Self {
+        self.client_info = Some(value.into());
+        self
+    }
+
+    /// 添加建立连接后执行的初始化 SQL，如 `SET` 命令、角色激活等
+    ///
+    /// 可多次调用，按添加顺序以普通查询执行，不占用预处理语句缓存；任一语句执行失败时建立连接失败。
+    pub fn init_sql(mut self, sql: impl Into<String>) -> Self {
+        self.init_sql.push(sql.into());
+        self
+    }
+
     /// Sets the character set for the connection.
     ///
     /// The default character set is `utf8mb4`. This is supported from MySQL 5.5.3.
@@ -301,6 +475,40 @@ impl XuguConnectOptions {
         self.statement_cache_capacity = capacity.clamp(100, 2097152);
         self
     }
+
+    /// 服务器返回的单个长度字段（如一个列值）允许的最大字节数
+    ///
+    /// 超过限制时返回协议错误，而不是按服务器给出的长度分配内存。
+    ///
+    /// 默认值为 1 GiB。
+    pub fn max_packet_size(mut self, size: usize) -> Self {
+        self.max_packet_size = size;
+        self
+    }
+
+    /// 单行数据所有列值合计允许的最大字节数
+    ///
+    /// 超过限制时返回协议错误。默认值为 1 GiB。
+    pub fn max_row_bytes(mut self, size: usize) -> Self {
+        self.max_row_bytes = size;
+        self
+    }
+
+    /// 绑定的十进制数（如 `rust_decimal::Decimal`、`BigDecimal`）超过 `NUMERIC` 参数声明的标度时的处理方式
+    ///
+    /// 发送前按预处理语句返回的参数精度和标度检查，整数部分超出精度时总是返回错误；
+    /// 不经过预处理直接执行的语句不检查，字符串参数原样发送、由服务器转换。
+    /// 默认值为 [`XuguNumericRounding::Error`]。
+    ///
+    /// ```rust
+    /// # use sqlx_xugu::{XuguConnectOptions, XuguNumericRounding};
+    /// let options = XuguConnectOptions::new()
+    ///     .numeric_rounding(XuguNumericRounding::HalfEven);
+    /// ```
+    pub fn numeric_rounding(mut self, rounding: XuguNumericRounding) -> Self {
+        self.numeric_rounding = rounding;
+        self
+    }
 }
 
 impl XuguConnectOptions {
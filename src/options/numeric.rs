@@ -0,0 +1,52 @@
+use sqlx_core::Error;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// 绑定到 `NUMERIC` 参数的十进制数小数位数超过参数声明的标度（scale）时的处理方式
+///
+/// 只对预处理语句生效，参数的精度和标度由服务器在预处理时返回；
+/// 整数部分的位数超过精度时总是返回错误。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum XuguNumericRounding {
+    /// 返回错误，错误信息中包含参数的位置和名称
+    #[default]
+    Error,
+    /// 四舍六入五成双（银行家舍入）
+    HalfEven,
+    /// 直接截断多余的小数位
+    Truncate,
+}
+
+impl XuguNumericRounding {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            XuguNumericRounding::Error => "error",
+            XuguNumericRounding::HalfEven => "half_even",
+            XuguNumericRounding::Truncate => "truncate",
+        }
+    }
+}
+
+impl Display for XuguNumericRounding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for XuguNumericRounding {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "error" => Ok(XuguNumericRounding::Error),
+            "half_even" | "round_half_even" => Ok(XuguNumericRounding::HalfEven),
+            "truncate" => Ok(XuguNumericRounding::Truncate),
+            _ => Err(Error::Configuration(
+                format!(
+                    "invalid value {s:?} for `numeric_rounding`, expected `error`, `half_even` or `truncate`"
+                )
+                .into(),
+            )),
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use crate::io::{AsyncStreamExt, StreamDecode};
+use crate::io::{AsyncStreamExt, StreamDecode, MAX_PREALLOC};
 use crate::protocol::message::{BackendMessage, BackendMessageFormat};
 use crate::protocol::statement::ParameterDef;
 use crate::protocol::ServerContext;
@@ -17,8 +17,8 @@ impl BackendMessage for ParameterDescription {
         cnt: ServerContext,
     ) -> Result<Self, Error> {
         // 读取服务器返回的参数信息
-        let num = stream.read_i32().await?;
-        let mut params = Vec::with_capacity(num as usize);
+        let num = stream.read_count().await?;
+        let mut params = Vec::with_capacity(num.min(MAX_PREALLOC));
         for _ in 0..num {
             let def = ParameterDef::decode_with(stream, cnt).await?;
             params.push(def);
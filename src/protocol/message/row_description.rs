@@ -1,4 +1,4 @@
-use crate::io::{AsyncStreamExt, StreamDecode};
+use crate::io::{AsyncStreamExt, StreamDecode, MAX_PREALLOC};
 use crate::protocol::message::{BackendMessage, BackendMessageFormat};
 use crate::protocol::text::ColumnDefinition;
 use crate::protocol::ServerContext;
@@ -58,8 +58,8 @@ impl BackendMessage for RowDescription {
         stream: &mut S,
         cnt: ServerContext,
     ) -> Result<Self, Error> {
-        let num_columns = stream.read_i32().await?;
-        let mut fields = Vec::with_capacity(num_columns as usize);
+        let num_columns = stream.read_count().await?;
+        let mut fields = Vec::with_capacity(num_columns.min(MAX_PREALLOC));
 
         for _ in 0..num_columns {
             let def = ColumnDefinition::decode_with(stream, cnt).await?;
@@ -3,47 +3,106 @@ use crate::connection::StatementId;
 use crate::protocol::encode_sql_command;
 use crate::protocol::statement::ParameterDef;
 use crate::protocol::text::ColumnType;
-use crate::XuguArguments;
+use crate::types::fit_numeric;
+use crate::{XuguArguments, XuguNumericRounding};
 use bytes::BufMut;
 use sqlx_core::io::ProtocolEncode;
 use sqlx_core::Error;
+use std::borrow::Cow;
 
 #[derive(Debug)]
 pub struct Execute<'q, 'p> {
     pub st_id: StatementId,
     pub arguments: &'q XuguArguments<'q>,
     pub params: &'p Vec<ParameterDef>,
+    pub numeric_rounding: XuguNumericRounding,
 }
 
-impl Execute<'_, '_> {
-    fn encode_params(&self, buf: &mut Vec<u8>) {
-        let params = &self.params;
-        let args = &self.arguments.values;
-        let types = &self.arguments.types;
-
-        let args_count = args.len() as i32;
-        buf.put_i32(args_count);
-
-        for i in 0..args.len() {
-            let param_name = &params[i].param_name;
-            buf.put_i16(param_name.len() as i16);
-            buf.put_slice(param_name.as_bytes());
-            let inout_type = params[i].ordinal;
-            buf.put_i16(inout_type as i16);
-
-            let type_id = types[i].r#type as i32;
-            let (arg, type_id) = match &args[i] {
-                XuguArgumentValue::Null => ([].as_slice(), ColumnType::NULL as i32),
-                XuguArgumentValue::Str(x) => (x.as_bytes(), type_id),
-                XuguArgumentValue::Bin(x) => (x.as_ref(), type_id),
-                XuguArgumentValue::Bytes(x) => (x.as_ref(), type_id),
-            };
-            buf.put_i16(type_id as i16);
-
-            buf.put_i32(arg.len() as i32);
-            buf.put_slice(arg);
-        }
+/// 直接执行带参数的 SQL，不需要先发送 `Prepare`
+///
+/// 没有服务器返回的参数定义，每个参数按以下取值编码：
+///
+/// * 参数名为空，即按 `?` 占位符的位置绑定；
+/// * 输入输出类型为 [`PARAM_IN`]。
+///
+/// 两者与 `Prepare` 后服务器为 `?` 占位符返回的参数定义一致（参数名为空，输入输出类型为 0，
+/// 加 1 后即 `PARAM_IN`），因此同一条语句直接执行与预处理后执行发送的参数完全相同。
+/// 存储过程的输出参数需要服务器返回的定义，不能直接执行。
+#[derive(Debug)]
+pub struct ExecuteDirect<'q> {
+    pub sql: &'q str,
+    pub arguments: &'q XuguArguments<'q>,
+}
+
+/// 输入参数的输入输出类型
+///
+/// 服务器在参数定义中以 0 开始编号（0 为输入参数），客户端发送时加 1，
+/// 见 [`ParameterDef::ordinal`]。
+const PARAM_IN: i32 = 1;
+
+fn encode_params(
+    buf: &mut Vec<u8>,
+    arguments: &XuguArguments<'_>,
+    params: Option<(&[ParameterDef], XuguNumericRounding)>,
+) -> Result<(), Error> {
+    let args = &arguments.values;
+    let types = &arguments.types;
+
+    let args_count = args.len() as i32;
+    buf.put_i32(args_count);
+
+    for i in 0..args.len() {
+        let param = params.and_then(|(params, rounding)| Some((params.get(i)?, rounding)));
+        let (param_name, inout_type) = match param {
+            Some((param, _)) => (param.param_name.as_str(), param.ordinal),
+            None => ("", PARAM_IN),
+        };
+        buf.put_i16(param_name.len() as i16);
+        buf.put_slice(param_name.as_bytes());
+        buf.put_i16(inout_type as i16);
+
+        let type_id = types[i].r#type as i32;
+        let (arg, type_id) = match &args[i] {
+            XuguArgumentValue::Null => (Cow::Borrowed([].as_slice()), ColumnType::NULL as i32),
+            XuguArgumentValue::Str(x) => (Cow::Borrowed(x.as_bytes()), type_id),
+            XuguArgumentValue::Numeric(x) => match param {
+                Some((param, rounding)) => match fit_param(i, param, x, rounding)? {
+                    Cow::Borrowed(x) => (Cow::Borrowed(x.as_bytes()), type_id),
+                    Cow::Owned(x) => (Cow::Owned(x.into_bytes()), type_id),
+                },
+                None => (Cow::Borrowed(x.as_bytes()), type_id),
+            },
+            XuguArgumentValue::Bin(x) => (Cow::Borrowed(x.as_ref()), type_id),
+            XuguArgumentValue::Bytes(x) => (Cow::Borrowed(x.as_ref()), type_id),
+        };
+        buf.put_i16(type_id as i16);
+
+        buf.put_i32(arg.len() as i32);
+        buf.put_slice(&arg);
     }
+
+    Ok(())
+}
+
+/// 按 `NUMERIC` 参数声明的精度和标度检查、舍入十进制数类型（`XgNumeric`、`BigDecimal`、`Decimal` 等）的参数，
+/// 其他以文本发送的参数（如 `&str`）原样发送
+fn fit_param<'a>(
+    index: usize,
+    param: &ParameterDef,
+    value: &'a str,
+    rounding: XuguNumericRounding,
+) -> Result<Cow<'a, str>, Error> {
+    if param.r#type != ColumnType::NUMERIC || param.precision <= 0 || param.scale < 0 {
+        return Ok(Cow::Borrowed(value));
+    }
+
+    fit_numeric(value, param.precision as u32, param.scale as u32, rounding).map_err(|e| {
+        let name = match param.param_name.as_str() {
+            "" => format!("parameter {}", index + 1),
+            name => format!("parameter {} ({name})", index + 1),
+        };
+        Error::Encode(format!("{name}: {e}").into())
+    })
 }
 
 impl ProtocolEncode<'_, ()> for Execute<'_, '_> {
@@ -51,7 +110,115 @@ impl ProtocolEncode<'_, ()> for Execute<'_, '_> {
         let sql_cmd = format!("? {}", self.st_id);
 
         encode_sql_command(buf, &sql_cmd);
-        self.encode_params(buf);
-        Ok(())
+        encode_params(
+            buf,
+            self.arguments,
+            Some((self.params, self.numeric_rounding)),
+        )
+    }
+}
+
+impl ProtocolEncode<'_, ()> for ExecuteDirect<'_> {
+    fn encode_with(&self, buf: &mut Vec<u8>, _: ()) -> Result<(), Error> {
+        encode_sql_command(buf, self.sql);
+        encode_params(buf, self.arguments, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args() -> XuguArguments<'static> {
+        let mut arguments = XuguArguments::default();
+        arguments.add(42_i32).unwrap();
+        arguments.add("x").unwrap();
+        arguments.add(Option::<i32>::None).unwrap();
+        arguments
+    }
+
+    /// 跳过命令头 `'?' len sql '\0'`，返回参数部分
+    fn params_of(buf: &[u8]) -> &[u8] {
+        let len = u32::from_be_bytes(buf[1..5].try_into().unwrap()) as usize;
+        &buf[1 + 4 + len + 1..]
+    }
+
+    #[test]
+    fn execute_direct_matches_prepared_input_params() {
+        let arguments = args();
+
+        // 服务器为 `?` 占位符返回的参数定义：参数名为空，输入参数
+        let params = (0..3)
+            .map(|_| ParameterDef {
+                param_name: String::new(),
+                ordinal: PARAM_IN,
+                r#type: ColumnType::CHAR,
+                precision: 0,
+                scale: 0,
+            })
+            .collect();
+
+        let mut prepared = Vec::new();
+        Execute {
+            st_id: StatementId::NAMED_START,
+            arguments: &arguments,
+            params: &params,
+            numeric_rounding: XuguNumericRounding::default(),
+        }
+        .encode_with(&mut prepared, ())
+        .unwrap();
+
+        let mut direct = Vec::new();
+        ExecuteDirect {
+            sql: "SELECT ?, ?, ?",
+            arguments: &arguments,
+        }
+        .encode_with(&mut direct, ())
+        .unwrap();
+
+        assert_eq!(params_of(&prepared), params_of(&direct));
+
+        let params = params_of(&direct);
+        assert_eq!(params[..4], 3_i32.to_be_bytes());
+        // 参数名长度 0，输入输出类型 1
+        assert_eq!(params[4..8], [0, 0, 0, 1]);
+    }
+
+    #[test]
+    fn only_decimal_params_are_fitted() {
+        let mut arguments = XuguArguments::default();
+        arguments.add("12.34").unwrap();
+        arguments
+            .add("12.34".parse::<crate::types::XgNumeric>().unwrap())
+            .unwrap();
+
+        let params = (0..2)
+            .map(|_| ParameterDef {
+                param_name: String::new(),
+                ordinal: PARAM_IN,
+                r#type: ColumnType::NUMERIC,
+                precision: 3,
+                scale: 1,
+            })
+            .collect();
+
+        let mut buf = Vec::new();
+        Execute {
+            st_id: StatementId::NAMED_START,
+            arguments: &arguments,
+            params: &params,
+            numeric_rounding: XuguNumericRounding::HalfEven,
+        }
+        .encode_with(&mut buf, ())
+        .unwrap();
+
+        // 字符串参数原样发送，由服务器转换
+        let params = params_of(&buf);
+        let first = &params[4 + 2 + 2 + 2..];
+        assert_eq!(first[..4], 5_i32.to_be_bytes());
+        assert_eq!(&first[4..9], b"12.34");
+        let second = &first[9 + 2 + 2 + 2..];
+        assert_eq!(second[..4], 4_i32.to_be_bytes());
+        assert_eq!(&second[4..8], b"12.3");
     }
 }
@@ -3,7 +3,7 @@ mod prepare;
 mod prepare_ok;
 mod stmt_close;
 
-pub(crate) use execute::Execute;
+pub(crate) use execute::{Execute, ExecuteDirect};
 pub(crate) use prepare::Prepare;
 pub(crate) use prepare_ok::ParameterDef;
 pub(crate) use stmt_close::StmtClose;
@@ -4,9 +4,9 @@ use crate::protocol::text::ColumnType;
 use crate::protocol::ServerContext;
 
 #[derive(Debug)]
-#[allow(unused)]
 pub(crate) struct ParameterDef {
     pub(crate) param_name: String,
+    /// 参数的输入输出类型，服务器以 0 开始编号，这里已加 1，执行时原样发送
     pub(crate) ordinal: i32,
     pub(crate) r#type: ColumnType,
     pub(crate) precision: i32,
@@ -727,12 +727,12 @@ impl StreamDecode<ServerContext> for ColumnDefinition {
                 alias = name.clone();
             }
         } else {
-            let len = stream.read_i32().await?;
+            let len = stream.read_len().await?;
             // NAME1
             // NAME1%alias
             // table.NAME1%alias
             // Schema.table.NAME1%alias
-            let mut total_name = stream.read_bytes(len as usize).await?;
+            let mut total_name = stream.read_bytes(len).await?;
 
             //别名
             if let Some(pos) = total_name.iter().rposition(|&x| x == b'%') {
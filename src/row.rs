@@ -14,6 +14,7 @@ pub struct XuguRow {
     pub(crate) row: Arc<Vec<Bytes>>,
     pub(crate) columns: Arc<Vec<XuguColumn>>,
     pub(crate) column_names: Arc<HashMap<UStr, usize>>,
+    pub(crate) time_zone: Option<i32>,
 }
 
 impl Row for XuguRow {
@@ -35,6 +36,7 @@ impl Row for XuguRow {
             row: Some(&self.row),
             type_info: column.type_info.clone(),
             value,
+            time_zone: self.time_zone,
         })
     }
 }
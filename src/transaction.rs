@@ -40,6 +40,15 @@ impl TransactionManager for XuguTransactionManager {
         Box::pin(async move {
             let depth = conn.inner.transaction_depth;
             if depth > 0 {
+                // XA 分支需要经过两阶段提交，不能直接 COMMIT
+                if depth == 1 {
+                    if let Some(xid) = &conn.inner.xa_xid {
+                        return Err(Error::InvalidArgument(format!(
+                            "the XA transaction ({xid}) must be committed with prepare_transaction and commit_prepared"
+                        )));
+                    }
+                }
+
                 // 虚谷 v11 不支持 事务保存点的释放 RELEASE SAVEPOINT _sqlx_savepoint_1
                 // 所以忽略  RELEASE SAVEPOINT 的执行，只执行最后的的 COMMIT
                 if depth == 1 {
@@ -47,6 +56,7 @@ impl TransactionManager for XuguTransactionManager {
                 }
 
                 conn.inner.transaction_depth = depth - 1;
+                end_xa(conn);
             }
 
             Ok(())
@@ -58,8 +68,11 @@ impl TransactionManager for XuguTransactionManager {
             let depth = conn.inner.transaction_depth;
 
             if depth > 0 {
-                conn.execute(&*rollback_ansi_transaction_sql(depth)).await?;
+                for sql in rollback_sql(conn, depth) {
+                    conn.execute(&*sql).await?;
+                }
                 conn.inner.transaction_depth = depth - 1;
+                end_xa(conn);
             }
 
             Ok(())
@@ -75,14 +88,17 @@ impl TransactionManager for XuguTransactionManager {
         let depth = conn.inner.transaction_depth;
 
         if depth > 0 {
-            conn.inner
-                .stream
-                .write_packet(Query(&rollback_ansi_transaction_sql(depth)))
-                .expect("BUG: unexpected error queueing ROLLBACK");
-            // Queue a simple query (not prepared) to execute the next time this connection is used.
-            conn.inner.pending_ready_for_query_count += 1;
+            for sql in rollback_sql(conn, depth) {
+                conn.inner
+                    .stream
+                    .write_packet(Query(&sql))
+                    .expect("BUG: unexpected error queueing ROLLBACK");
+                // Queue a simple query (not prepared) to execute the next time this connection is used.
+                conn.inner.pending_ready_for_query_count += 1;
+            }
 
             conn.inner.transaction_depth = depth - 1;
+            end_xa(conn);
         }
     }
 
@@ -90,3 +106,20 @@ impl TransactionManager for XuguTransactionManager {
         conn.inner.transaction_depth
     }
 }
+
+/// 回滚第 `depth` 层事务的语句
+///
+/// `begin_xa` 开启的 XA 分支需要先 `XA END` 再 `XA ROLLBACK`，普通的 `ROLLBACK` 不会结束该分支。
+fn rollback_sql(conn: &XuguConnection, depth: usize) -> Vec<String> {
+    match &conn.inner.xa_xid {
+        Some(xid) if depth == 1 => vec![format!("XA END {xid}"), format!("XA ROLLBACK {xid}")],
+        _ => vec![rollback_ansi_transaction_sql(depth).into_owned()],
+    }
+}
+
+/// 事务结束后，`begin_xa` 开启的 XA 分支不能再进入准备阶段
+fn end_xa(conn: &mut XuguConnection) {
+    if conn.inner.transaction_depth == 0 {
+        conn.inner.xa_xid = None;
+    }
+}
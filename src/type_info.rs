@@ -20,6 +20,26 @@ impl XuguTypeInfo {
         }
     }
 
+    /// 自定义 `OBJECT` 类型，用于为组合类型实现 [`Type`][sqlx_core::types::Type]
+    pub const fn object() -> Self {
+        Self::binary(ColumnType::OBJECT)
+    }
+
+    /// 自定义 `RECORD` 类型
+    pub const fn record() -> Self {
+        Self::binary(ColumnType::RECORD)
+    }
+
+    /// `OBJECT` 类型的数组，用于为组合类型实现 [`XuguHasArrayType`][crate::types::XuguHasArrayType]
+    pub const fn object_array() -> Self {
+        Self::binary(ColumnType::ARRAY_OBJECT)
+    }
+
+    /// 是否为自定义组合类型 `OBJECT` 或 `RECORD`
+    pub fn is_composite(&self) -> bool {
+        matches!(self.r#type, ColumnType::OBJECT | ColumnType::RECORD)
+    }
+
     #[doc(hidden)]
     pub fn __type_feature_gate(&self) -> Option<&'static str> {
         match self.r#type {
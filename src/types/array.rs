@@ -0,0 +1,556 @@
+use crate::arguments::XuguArgumentValue;
+use crate::protocol::text::{ColumnFlags, ColumnType};
+use crate::{Xugu, XuguTypeInfo, XuguValueRef};
+use sqlx_core::decode::Decode;
+use sqlx_core::encode::{Encode, IsNull};
+use sqlx_core::error::BoxDynError;
+use sqlx_core::types::Type;
+use std::borrow::Cow;
+
+/// 可以作为数组（`ARRAY_*`）元素的类型
+///
+/// 数组按文本字面量 `{1,2,NULL}` 编解码，元素需要提供自身的文本表示。
+/// 为自定义类型实现该 trait 后即可使用 `Vec<T>` 和 `&[T]` 读写数组列。
+///
+/// 已实现的元素类型：
+///
+/// | Rust 类型 | 数组类型 |
+/// |-----------|----------|
+/// | `bool` | `ARRAY_BOOLEAN` |
+/// | `i8`、`i16`、`i32`、`i64` | `ARRAY_TINYINT`、`ARRAY_SMALLINT`、`ARRAY_INTEGER`、`ARRAY_BIGINT` |
+/// | `u16`、`u32`、`u64` | `ARRAY_INTEGER`、`ARRAY_BIGINT`、`ARRAY_NUMERIC`，可读取任意整数数组 |
+/// | `f32`、`f64` | `ARRAY_FLOAT`、`ARRAY_DOUBLE` |
+/// | `String` | `ARRAY_CHAR`、`ARRAY_NCHAR` |
+/// | [`XgNumeric`][crate::types::XgNumeric]、`BigDecimal`、`Decimal` | `ARRAY_NUMERIC` |
+/// | `Uuid` | `ARRAY_GUID` |
+/// | `Json<T>` | `ARRAY_JSON` |
+/// | `Vec<T>` | 同 `T`，用于嵌套集合，如 `Vec<Vec<i32>>` |
+/// | chrono `NaiveDate`、`NaiveTime`、`NaiveDateTime` | `ARRAY_DATE`、`ARRAY_TIME`、`ARRAY_DATETIME` |
+/// | chrono `DateTime<Utc>`、`DateTime<Local>`、`DateTime<FixedOffset>` | `ARRAY_DATETIME_TZ` |
+/// | time `Date`、`Time`、`PrimitiveDateTime`、`OffsetDateTime` | `ARRAY_DATE`、`ARRAY_TIME`、`ARRAY_DATETIME`、`ARRAY_DATETIME_TZ` |
+///
+/// 以下数组类型没有对应的实现，需要在 SQL 中转换为其他类型，或为自定义类型实现该 trait：
+///
+/// * `u8`：`Vec<u8>` 和 `&[u8]` 表示二进制数据，可以用 `Vec<u16>` 等更宽的类型代替；
+/// * `ARRAY_TIME_TZ`：chrono 和 time 都没有表示带时区的时刻的类型；
+/// * `ARRAY_INTERVAL_*`：元素的文本表示（如 `3` 表示 3 天还是 3 小时）取决于列的时间间隔类型，
+///   而元素的编解码不知道列的类型，因此 [`XgInterval`][crate::types::XgInterval]、chrono `TimeDelta`、
+///   time `Duration` 和 `std::time::Duration` 不能作为数组元素；
+/// * 几何类型数组 `ARRAY_POINT`、`ARRAY_LSEG`、`ARRAY_LINE`、`ARRAY_BOX`、`ARRAY_PATH`、
+///   `ARRAY_POLYGON`、`ARRAY_CIRCLE` 及 `ARRAY_*_OLD`：元素的文本表示本身包含逗号，
+///   如 `<(1,2),3>`、`(1,2),(3,4)`，服务器返回未加引号的元素时无法与数组的分隔符区分；
+/// * 空间类型数组 `ARRAY_GEOMETRY`、`ARRAY_GEOGRAPHY`、`ARRAY_BOX2D`、`ARRAY_BOX3D`、
+///   `ARRAY_SPHEROID`、`ARRAY_RASTER`：元素以 WKB 等二进制格式传输，没有文本表示；
+/// * `ARRAY_BINARY`、`ARRAY_CLOB`、`ARRAY_BLOB*`、`ARRAY_BIT`、`ARRAY_VARBIT`、`ARRAY_ROWID`、
+///   `ARRAY_ROWVERSION` 等其余类型：没有确定的元素文本格式。
+///
+/// 以上类型可以在 SQL 中将数组转换为字符串数组，读取为 `Vec<String>` 后再逐个解析元素。
+///
+/// ```rust
+/// # use sqlx_xugu::types::XuguHasArrayType;
+/// # use sqlx_xugu::XuguTypeInfo;
+/// # use sqlx_core::error::BoxDynError;
+/// struct Code(String);
+///
+/// impl XuguHasArrayType for Code {
+///     fn array_type_info() -> XuguTypeInfo {
+///         <String as XuguHasArrayType>::array_type_info()
+///     }
+///
+///     fn encode_element(&self) -> Result<Option<String>, BoxDynError> {
+///         Ok(Some(self.0.clone()))
+///     }
+///
+///     fn decode_element(text: Option<&str>) -> Result<Self, BoxDynError> {
+///         String::decode_element(text).map(Code)
+///     }
+/// }
+/// ```
+pub trait XuguHasArrayType: Sized {
+    /// 对应的数组类型，如 `i32` 对应 `ARRAY_INTEGER`
+    fn array_type_info() -> XuguTypeInfo;
+
+    /// 数组列的类型是否可以解码为该元素类型的数组
+    ///
+    /// 默认接受对应的数组类型，以及 `ARRAY` 和自定义集合类型 `VARRAY`、`TABLE`、`ITABLE`。
+    fn array_compatible(ty: &XuguTypeInfo) -> bool {
+        ty.r#type == Self::array_type_info().r#type || is_collection(ty.r#type)
+    }
+
+    /// 元素的文本表示，`None` 表示 NULL
+    fn encode_element(&self) -> Result<Option<String>, BoxDynError>;
+
+    /// 从元素的文本表示解码，`None` 表示 NULL
+    fn decode_element(text: Option<&str>) -> Result<Self, BoxDynError>;
+}
+
+impl<T: XuguHasArrayType> XuguHasArrayType for Option<T> {
+    fn array_type_info() -> XuguTypeInfo {
+        T::array_type_info()
+    }
+
+    fn array_compatible(ty: &XuguTypeInfo) -> bool {
+        T::array_compatible(ty)
+    }
+
+    fn encode_element(&self) -> Result<Option<String>, BoxDynError> {
+        match self {
+            Some(value) => value.encode_element(),
+            None => Ok(None),
+        }
+    }
+
+    fn decode_element(text: Option<&str>) -> Result<Self, BoxDynError> {
+        text.map(|text| T::decode_element(Some(text))).transpose()
+    }
+}
+
+/// 嵌套集合，如 `Vec<Vec<i32>>`，元素为子数组的字面量
+///
+/// 编码时子数组加引号，如 `{"{1,2}","{3}"}`；解码时同时接受 `{{1,2},{3}}`。
+impl<T: XuguHasArrayType> XuguHasArrayType for Vec<T> {
+    fn array_type_info() -> XuguTypeInfo {
+        T::array_type_info()
+    }
+
+    fn array_compatible(ty: &XuguTypeInfo) -> bool {
+        T::array_compatible(ty)
+    }
+
+    fn encode_element(&self) -> Result<Option<String>, BoxDynError> {
+        encode_array(self).map(Some)
+    }
+
+    fn decode_element(text: Option<&str>) -> Result<Self, BoxDynError> {
+        parse_array(non_null(text)?)?
+            .iter()
+            .map(|element| T::decode_element(element.as_deref()))
+            .collect()
+    }
+}
+
+pub(crate) fn array_type(ty: ColumnType) -> XuguTypeInfo {
+    XuguTypeInfo {
+        r#type: ty,
+        flags: ColumnFlags::empty(),
+    }
+}
+
+/// 不限定元素类型的集合
+pub(crate) fn is_collection(ty: ColumnType) -> bool {
+    matches!(
+        ty,
+        ColumnType::ARRAY | ColumnType::VARRAY | ColumnType::TABLE | ColumnType::ITABLE
+    )
+}
+
+pub(crate) fn non_null(text: Option<&str>) -> Result<&str, BoxDynError> {
+    text.ok_or_else(|| "unexpected NULL array element for a non-nullable type".into())
+}
+
+/// 通过 [`Display`][std::fmt::Display] 和 [`FromStr`][std::str::FromStr] 转换元素的文本表示
+macro_rules! impl_has_array_type {
+    ($($ty:ty => $array:ident),* $(,)?) => {
+        $(
+            impl $crate::types::XuguHasArrayType for $ty {
+                fn array_type_info() -> $crate::XuguTypeInfo {
+                    $crate::types::array::array_type($crate::protocol::text::ColumnType::$array)
+                }
+
+                fn encode_element(&self) -> Result<Option<String>, sqlx_core::error::BoxDynError> {
+                    Ok(Some(self.to_string()))
+                }
+
+                fn decode_element(text: Option<&str>) -> Result<Self, sqlx_core::error::BoxDynError> {
+                    $crate::types::array::non_null(text)?.trim().parse().map_err(Into::into)
+                }
+            }
+        )*
+    };
+}
+
+#[allow(unused_imports)] // only used by optional type integrations
+pub(crate) use impl_has_array_type;
+
+impl_has_array_type!(
+    i8 => ARRAY_TINYINT,
+    i16 => ARRAY_SMALLINT,
+    i32 => ARRAY_INTEGER,
+    i64 => ARRAY_BIGINT,
+    f32 => ARRAY_FLOAT,
+    f64 => ARRAY_DOUBLE,
+);
+
+impl XuguHasArrayType for bool {
+    fn array_type_info() -> XuguTypeInfo {
+        array_type(ColumnType::ARRAY_BOOLEAN)
+    }
+
+    fn encode_element(&self) -> Result<Option<String>, BoxDynError> {
+        Ok(Some(self.to_string()))
+    }
+
+    fn decode_element(text: Option<&str>) -> Result<Self, BoxDynError> {
+        let text = non_null(text)?.trim();
+        match text.to_ascii_lowercase().as_str() {
+            "t" | "true" | "1" | "on" => Ok(true),
+            "f" | "false" | "0" | "off" => Ok(false),
+            _ => Err(format!("unexpected value {text:?} for boolean array element").into()),
+        }
+    }
+}
+
+impl XuguHasArrayType for String {
+    fn array_type_info() -> XuguTypeInfo {
+        array_type(ColumnType::ARRAY_CHAR)
+    }
+
+    fn array_compatible(ty: &XuguTypeInfo) -> bool {
+        matches!(ty.r#type, ColumnType::ARRAY_CHAR | ColumnType::ARRAY_NCHAR)
+            || is_collection(ty.r#type)
+    }
+
+    fn encode_element(&self) -> Result<Option<String>, BoxDynError> {
+        Ok(Some(self.clone()))
+    }
+
+    fn decode_element(text: Option<&str>) -> Result<Self, BoxDynError> {
+        non_null(text).map(ToOwned::to_owned)
+    }
+}
+
+/// 生成数组字面量 `{a,"b c",NULL}`
+pub(crate) fn encode_array<T: XuguHasArrayType>(values: &[T]) -> Result<String, BoxDynError> {
+    let mut buf = String::from("{");
+    for (i, value) in values.iter().enumerate() {
+        if i > 0 {
+            buf.push(',');
+        }
+        match value.encode_element()? {
+            Some(text) => push_element(&mut buf, &text),
+            None => buf.push_str("NULL"),
+        }
+    }
+    buf.push('}');
+
+    Ok(buf)
+}
+
+/// 元素为空、为 `NULL` 或包含特殊字符时加双引号，并用 `\` 转义 `"` 和 `\`
+pub(crate) fn push_element(buf: &mut String, text: &str) {
+    let needs_quote = text.is_empty()
+        || text.eq_ignore_ascii_case("NULL")
+        || text.chars().any(|c| {
+            matches!(c, ',' | '{' | '}' | '[' | ']' | '(' | ')' | '"' | '\\') || c.is_whitespace()
+        });
+
+    if !needs_quote {
+        buf.push_str(text);
+        return;
+    }
+
+    buf.push('"');
+    for c in text.chars() {
+        if c == '"' || c == '\\' {
+            buf.push('\\');
+        }
+        buf.push(c);
+    }
+    buf.push('"');
+}
+
+/// 解析数组字面量，支持 `{...}` 和 `[...]`，未加引号的 `NULL` 为空元素
+///
+/// 未加引号的子数组 `{{1,2},{3}}` 和组合类型 `{(1,a),(2,b)}` 各作为一个元素返回。
+pub(crate) fn parse_array(text: &str) -> Result<Vec<Option<String>>, BoxDynError> {
+    let text = text.trim();
+    let inner = text
+        .strip_prefix('{')
+        .and_then(|s| s.strip_suffix('}'))
+        .or_else(|| text.strip_prefix('[').and_then(|s| s.strip_suffix(']')))
+        .ok_or_else(|| format!("invalid array literal {text:?}"))?;
+
+    parse_elements(inner, text, false)
+}
+
+/// 解析以逗号分隔的元素
+///
+/// 未加引号的元素以 `(`、`{` 或 `[` 开头时为组合类型或子数组，括号成对匹配，
+/// 括号内的逗号和加引号的内容不分隔元素，如 `{(1,"a,b"),{2,3}}` 中的 `(1,"a,b")` 和 `{2,3}`；
+/// 其他位置的括号是普通字符，如 `{f(x),y}` 的第一个元素为 `f(x)`。
+/// `nested` 为 `true` 时（组合类型的字段），任意位置的括号都成对匹配，如 `(f(x,y),1)`。
+pub(crate) fn parse_elements(
+    inner: &str,
+    text: &str,
+    nested: bool,
+) -> Result<Vec<Option<String>>, BoxDynError> {
+    let mut elements = Vec::new();
+    if inner.trim().is_empty() {
+        return Ok(elements);
+    }
+
+    let unterminated = || format!("unterminated quote in literal {text:?}");
+
+    let mut chars = inner.chars().peekable();
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+
+        let element = if chars.next_if_eq(&'"').is_some() {
+            let mut element = String::new();
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some('\\') => element.extend(chars.next()),
+                    Some(c) => element.push(c),
+                    None => return Err(unterminated().into()),
+                }
+            }
+            Some(element)
+        } else {
+            let nests = nested || matches!(chars.peek(), Some('(' | '{' | '['));
+            let mut element = String::new();
+            let mut depth = 0usize;
+            while let Some(c) = chars.next_if(|c| depth > 0 || *c != ',') {
+                element.push(c);
+                match c {
+                    '{' | '[' | '(' if nests => depth += 1,
+                    '}' | ']' | ')' if depth > 0 => depth -= 1,
+                    // 括号内加引号的内容原样保留，由元素的解码再处理
+                    '"' if depth > 0 => loop {
+                        match chars.next() {
+                            Some('"') => break element.push('"'),
+                            Some('\\') => {
+                                element.push('\\');
+                                element.push(chars.next().ok_or_else(unterminated)?);
+                            }
+                            Some(c) => element.push(c),
+                            None => return Err(unterminated().into()),
+                        }
+                    },
+                    _ => {}
+                }
+            }
+            if depth > 0 {
+                return Err(format!("unbalanced brackets in literal {text:?}").into());
+            }
+            let element = element.trim_end();
+            (!element.eq_ignore_ascii_case("NULL")).then(|| element.to_owned())
+        };
+        elements.push(element);
+
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        match chars.next() {
+            Some(',') => continue,
+            None => break,
+            Some(c) => return Err(format!("unexpected character {c:?} in literal {text:?}").into()),
+        }
+    }
+
+    Ok(elements)
+}
+
+fn decode_array<T: XuguHasArrayType>(value: XuguValueRef<'_>) -> Result<Vec<T>, BoxDynError> {
+    parse_array(value.as_str()?)?
+        .iter()
+        .map(|element| T::decode_element(element.as_deref()))
+        .collect()
+}
+
+impl<T: XuguHasArrayType> Type<Xugu> for Vec<T> {
+    fn type_info() -> XuguTypeInfo {
+        T::array_type_info()
+    }
+
+    fn compatible(ty: &XuguTypeInfo) -> bool {
+        T::array_compatible(ty)
+    }
+}
+
+impl<T: XuguHasArrayType> Type<Xugu> for [T] {
+    fn type_info() -> XuguTypeInfo {
+        T::array_type_info()
+    }
+
+    fn compatible(ty: &XuguTypeInfo) -> bool {
+        T::array_compatible(ty)
+    }
+}
+
+impl<T: XuguHasArrayType> Encode<'_, Xugu> for Vec<T> {
+    fn encode_by_ref(&self, args: &mut Vec<XuguArgumentValue<'_>>) -> Result<IsNull, BoxDynError> {
+        <&[T] as Encode<Xugu>>::encode_by_ref(&self.as_slice(), args)
+    }
+
+    fn produces(&self) -> Option<XuguTypeInfo> {
+        <&[T] as Encode<Xugu>>::produces(&self.as_slice())
+    }
+}
+
+impl<'q, T: XuguHasArrayType> Encode<'q, Xugu> for &'_ [T] {
+    fn encode_by_ref(&self, args: &mut Vec<XuguArgumentValue<'q>>) -> Result<IsNull, BoxDynError> {
+        args.push(XuguArgumentValue::Str(Cow::Owned(encode_array(self)?)));
+
+        Ok(IsNull::No)
+    }
+
+    // 以文本字面量发送，由服务器转换为数组
+    fn produces(&self) -> Option<XuguTypeInfo> {
+        Some(XuguTypeInfo::binary(ColumnType::CHAR))
+    }
+}
+
+impl<T: XuguHasArrayType> Decode<'_, Xugu> for Vec<T> {
+    fn decode(value: XuguValueRef<'_>) -> Result<Self, BoxDynError> {
+        decode_array(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip<T: XuguHasArrayType + PartialEq + std::fmt::Debug>(values: &[T]) -> String {
+        let text = encode_array(values).unwrap();
+        let decoded: Vec<T> = parse_array(&text)
+            .unwrap()
+            .iter()
+            .map(|element| T::decode_element(element.as_deref()))
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(decoded, values, "{text}");
+        text
+    }
+
+    #[test]
+    fn encode_quotes_special_elements() {
+        let values: Vec<Option<String>> = [
+            Some("plain"),
+            Some(""),
+            Some("NULL"),
+            Some("null"),
+            None,
+            Some("a,b"),
+            Some("{x}"),
+            Some("say \"hi\""),
+            Some("back\\slash"),
+            Some(" padded "),
+            Some("中文"),
+        ]
+        .into_iter()
+        .map(|s| s.map(String::from))
+        .collect();
+
+        let text = round_trip(&values);
+        assert_eq!(
+            text,
+            r#"{plain,"","NULL","null",NULL,"a,b","{x}","say \"hi\"","back\\slash"," padded ",中文}"#
+        );
+    }
+
+    #[test]
+    fn parse_array_literals() {
+        assert_eq!(parse_array("{}").unwrap(), Vec::<Option<String>>::new());
+        assert_eq!(
+            parse_array("  [ ]  ").unwrap(),
+            Vec::<Option<String>>::new()
+        );
+        assert_eq!(
+            parse_array("{ 1 , NULL,\"NULL\" ,null}").unwrap(),
+            [Some("1".into()), None, Some("NULL".into()), None]
+        );
+        assert_eq!(
+            parse_array("[a b, c]").unwrap(),
+            [Some("a b".into()), Some("c".into())]
+        );
+
+        // 元素中间的括号是普通字符
+        assert_eq!(
+            parse_array("{f(x),y)}").unwrap(),
+            [Some("f(x)".into()), Some("y)".into())]
+        );
+        // 未加引号的组合类型和子数组
+        assert_eq!(
+            parse_array(r#"{(1, a),(2,"b,)"), NULL,{3,{4}},[5]}"#).unwrap(),
+            [
+                Some("(1, a)".into()),
+                Some(r#"(2,"b,)")"#.into()),
+                None,
+                Some("{3,{4}}".into()),
+                Some("[5]".into())
+            ]
+        );
+
+        for invalid in [
+            "1,2", "{1,2", "{\"a}", "{\"a\"b}", "{(1,2}", "{{1},{2}", "{(\"a)}",
+        ] {
+            assert!(parse_array(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn numeric_elements() {
+        assert_eq!(round_trip(&[1_i32, -2, i32::MAX]), "{1,-2,2147483647}");
+        round_trip(&[Some(1.5_f64), None, Some(-0.25)]);
+        assert_eq!(round_trip(&[true, false]), "{true,false}");
+        assert_eq!(
+            Vec::<bool>::from_iter(
+                ["t", "F", "1", "off"].map(|t| bool::decode_element(Some(t)).unwrap())
+            ),
+            [true, false, true, false]
+        );
+        assert!(i32::decode_element(None).is_err());
+    }
+
+    #[test]
+    fn unsigned_elements() {
+        assert_eq!(round_trip(&[0_u16, u16::MAX]), "{0,65535}");
+        round_trip(&[0_u32, u32::MAX]);
+        assert_eq!(round_trip(&[u64::MAX]), "{18446744073709551615}");
+        assert!(u32::decode_element(Some("-1")).is_err());
+
+        for ty in [
+            ColumnType::ARRAY_TINYINT,
+            ColumnType::ARRAY_BIGINT,
+            ColumnType::ARRAY_NUMERIC,
+            ColumnType::ARRAY,
+        ] {
+            assert!(<Vec<u16> as Type<Xugu>>::compatible(&array_type(ty)));
+        }
+        assert!(!<Vec<u16> as Type<Xugu>>::compatible(&array_type(
+            ColumnType::ARRAY_CHAR
+        )));
+        assert_eq!(
+            <Vec<u64> as Type<Xugu>>::type_info().r#type,
+            ColumnType::ARRAY_NUMERIC
+        );
+    }
+
+    #[test]
+    fn encode_produces_char() {
+        let values = vec![1_i32, 2];
+        assert_eq!(
+            Encode::<Xugu>::produces(&values).map(|ty| ty.r#type),
+            Some(ColumnType::CHAR)
+        );
+        assert_eq!(
+            Encode::<Xugu>::produces(&values.as_slice()).map(|ty| ty.r#type),
+            Some(ColumnType::CHAR)
+        );
+    }
+
+    #[test]
+    fn nested_arrays() {
+        let values = vec![vec![1_i32, 2], vec![], vec![3]];
+        assert_eq!(round_trip(&values), r#"{"{1,2}","{}","{3}"}"#);
+
+        let decoded = Vec::<Vec<Option<i32>>>::decode_element(Some("{{1,NULL},{},[3]}")).unwrap();
+        assert_eq!(decoded, [vec![Some(1), None], vec![], vec![Some(3)]]);
+
+        assert!(Vec::<Vec<i32>>::decode_element(Some("{{1},2}")).is_err());
+        assert!(Vec::<Vec<i32>>::decode_element(Some("{NULL}")).is_err());
+        assert_eq!(
+            Vec::<Option<Vec<i32>>>::decode_element(Some("{NULL,{1}}")).unwrap(),
+            [None, Some(vec![1])]
+        );
+    }
+}
@@ -7,6 +7,7 @@ use std::borrow::Cow;
 
 use crate::arguments::XuguArgumentValue;
 use crate::protocol::text::ColumnType;
+use crate::types::array::impl_has_array_type;
 use crate::{Xugu, XuguTypeInfo, XuguValueRef};
 
 impl Type<Xugu> for BigDecimal {
@@ -21,7 +22,7 @@ impl Type<Xugu> for BigDecimal {
 
 impl<'q> Encode<'q, Xugu> for BigDecimal {
     fn encode_by_ref(&self, args: &mut Vec<XuguArgumentValue<'q>>) -> Result<IsNull, BoxDynError> {
-        args.push(XuguArgumentValue::Str(Cow::Owned(self.to_string())));
+        args.push(XuguArgumentValue::Numeric(Cow::Owned(self.to_string())));
 
         Ok(IsNull::No)
     }
@@ -36,3 +37,5 @@ impl Decode<'_, Xugu> for BigDecimal {
         Ok(value.as_str()?.parse()?)
     }
 }
+
+impl_has_array_type!(BigDecimal => ARRAY_NUMERIC);
@@ -0,0 +1,245 @@
+use crate::arguments::XuguArgumentValue;
+use crate::protocol::text::ColumnType;
+use crate::{Xugu, XuguTypeInfo, XuguValueRef};
+use sqlx_core::decode::Decode;
+use sqlx_core::encode::{Encode, IsNull};
+use sqlx_core::error::BoxDynError;
+use sqlx_core::types::Type;
+use std::borrow::Cow;
+use std::fmt::{Display, Formatter, Write};
+use std::str::FromStr;
+
+/// ## Xugu BIT / VARBIT type
+///
+/// 任意长度的位串，文本表示为 `0101`。
+///
+/// 位按高位在前的顺序存放在字节中，最后一个字节中多余的低位补 0，
+/// 因此长度不是 8 的倍数时也能原样保留。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct XgBitVec {
+    bytes: Vec<u8>,
+    len: usize,
+}
+
+impl XgBitVec {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 创建长度为 `len`、所有位为 0 的位串
+    pub fn with_len(len: usize) -> Self {
+        Self {
+            bytes: vec![0; (len + 7) / 8],
+            len,
+        }
+    }
+
+    /// 由高位在前的字节创建，只取前 `len` 位
+    ///
+    /// `len` 超出 `bytes` 的位数时返回 `None`。
+    pub fn from_bytes(bytes: &[u8], len: usize) -> Option<Self> {
+        let n = (len + 7) / 8;
+        if n > bytes.len() {
+            return None;
+        }
+
+        let mut bytes = bytes[..n].to_vec();
+        if len % 8 != 0 {
+            bytes[n - 1] &= 0xff << (8 - len % 8);
+        }
+
+        Some(Self { bytes, len })
+    }
+
+    /// 高位在前的字节，多余的位为 0
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, i: usize) -> Option<bool> {
+        (i < self.len).then(|| self.bytes[i / 8] & (0x80 >> (i % 8)) != 0)
+    }
+
+    /// 设置第 `i` 位
+    ///
+    /// # Panics
+    ///
+    /// `i` 超出长度时 panic。
+    pub fn set(&mut self, i: usize, value: bool) {
+        assert!(
+            i < self.len,
+            "bit index {i} out of range for length {}",
+            self.len
+        );
+
+        let mask = 0x80 >> (i % 8);
+        if value {
+            self.bytes[i / 8] |= mask;
+        } else {
+            self.bytes[i / 8] &= !mask;
+        }
+    }
+
+    pub fn push(&mut self, value: bool) {
+        if self.len % 8 == 0 {
+            self.bytes.push(0);
+        }
+        self.len += 1;
+        self.set(self.len - 1, value);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = bool> + '_ {
+        (0..self.len).map(|i| self.bytes[i / 8] & (0x80 >> (i % 8)) != 0)
+    }
+}
+
+impl FromIterator<bool> for XgBitVec {
+    fn from_iter<I: IntoIterator<Item = bool>>(iter: I) -> Self {
+        let mut bits = Self::new();
+        for bit in iter {
+            bits.push(bit);
+        }
+        bits
+    }
+}
+
+impl Type<Xugu> for XgBitVec {
+    fn type_info() -> XuguTypeInfo {
+        XuguTypeInfo::binary(ColumnType::VARBIT)
+    }
+
+    fn compatible(ty: &XuguTypeInfo) -> bool {
+        matches!(
+            ty.r#type,
+            ColumnType::BIT | ColumnType::VARBIT | ColumnType::CHAR | ColumnType::BINARY
+        )
+    }
+}
+
+impl Encode<'_, Xugu> for XgBitVec {
+    fn encode_by_ref(&self, args: &mut Vec<XuguArgumentValue>) -> Result<IsNull, BoxDynError> {
+        args.push(XuguArgumentValue::Str(Cow::Owned(self.to_string())));
+
+        Ok(IsNull::No)
+    }
+
+    fn produces(&self) -> Option<XuguTypeInfo> {
+        Some(XuguTypeInfo::binary(ColumnType::CHAR))
+    }
+}
+
+/// 按列的类型解码：`BINARY` 列按高位在前的字节解码，长度为字节数的 8 倍；
+/// `BIT`、`VARBIT` 和字符串列由服务器以 `0`、`1` 字符返回，按位串文本解析
+impl<'r> Decode<'r, Xugu> for XgBitVec {
+    fn decode(value: XuguValueRef<'r>) -> Result<Self, BoxDynError> {
+        if value.type_info.r#type == ColumnType::BINARY {
+            let bytes = value.as_bytes()?;
+            return Ok(Self {
+                bytes: bytes.to_vec(),
+                len: bytes.len() * 8,
+            });
+        }
+
+        value.as_str()?.parse()
+    }
+}
+
+impl Display for XgBitVec {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for bit in self.iter() {
+            f.write_char(if bit { '1' } else { '0' })?;
+        }
+        Ok(())
+    }
+}
+
+/// 解析 `0101`，也接受位串字面量 `B'0101'`
+impl FromStr for XgBitVec {
+    type Err = BoxDynError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let bits = s
+            .strip_prefix("B'")
+            .or_else(|| s.strip_prefix("b'"))
+            .and_then(|s| s.strip_suffix('\''))
+            .unwrap_or(s);
+
+        bits.chars()
+            .map(|c| match c {
+                '0' => Ok(false),
+                '1' => Ok(true),
+                _ => Err(format!("invalid bit string {s:?}").into()),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::text::ColumnFlags;
+
+    fn decode(ty: ColumnType, bytes: &[u8]) -> Result<XgBitVec, BoxDynError> {
+        XgBitVec::decode(XuguValueRef {
+            value: Some(bytes),
+            row: None,
+            type_info: XuguTypeInfo {
+                r#type: ty,
+                flags: ColumnFlags::empty(),
+            },
+            time_zone: None,
+        })
+    }
+
+    #[test]
+    fn decode_by_column_type() {
+        for ty in [ColumnType::BIT, ColumnType::VARBIT, ColumnType::CHAR] {
+            let bits = decode(ty, b"01").unwrap();
+            assert_eq!(bits.len(), 2);
+            assert_eq!(bits.to_string(), "01");
+            assert!(decode(ty, &[0x80]).is_err());
+            assert!(decode(ty, b"012").is_err());
+        }
+
+        // 二进制内容恰好是 `0`、`1` 字符时也不能当作文本
+        let bits = decode(ColumnType::BINARY, b"01").unwrap();
+        assert_eq!(bits.len(), 16);
+        assert_eq!(bits.as_bytes(), [0x30, 0x31]);
+        assert_eq!(bits.to_string(), "0011000000110001");
+    }
+
+    #[test]
+    fn text_round_trip_preserves_length() {
+        for text in [
+            "",
+            "0",
+            "1",
+            "0000000",
+            "101010101",
+            "1".repeat(129).as_str(),
+        ] {
+            let bits: XgBitVec = text.parse().unwrap();
+            assert_eq!(bits.len(), text.len());
+            assert_eq!(bits.to_string(), text);
+            assert_eq!(decode(ColumnType::VARBIT, text.as_bytes()).unwrap(), bits);
+        }
+        assert_eq!("B'101'".parse::<XgBitVec>().unwrap().to_string(), "101");
+    }
+
+    #[test]
+    fn from_bytes_clears_padding() {
+        let bits = XgBitVec::from_bytes(&[0xff, 0xff], 10).unwrap();
+        assert_eq!(bits.as_bytes(), [0xff, 0xc0]);
+        assert_eq!(bits, "1111111111".parse().unwrap());
+        assert!(XgBitVec::from_bytes(&[0xff], 9).is_none());
+    }
+}
@@ -0,0 +1,52 @@
+use crate::arguments::XuguArgumentValue;
+use crate::types::XgBitVec;
+use crate::{Xugu, XuguTypeInfo, XuguValueRef};
+use bit_vec::BitVec;
+use sqlx_core::decode::Decode;
+use sqlx_core::encode::{Encode, IsNull};
+use sqlx_core::error::BoxDynError;
+use sqlx_core::types::Type;
+
+impl From<&BitVec> for XgBitVec {
+    fn from(value: &BitVec) -> Self {
+        value.iter().collect()
+    }
+}
+
+impl From<BitVec> for XgBitVec {
+    fn from(value: BitVec) -> Self {
+        Self::from(&value)
+    }
+}
+
+impl From<XgBitVec> for BitVec {
+    fn from(value: XgBitVec) -> Self {
+        value.iter().collect()
+    }
+}
+
+impl Type<Xugu> for BitVec {
+    fn type_info() -> XuguTypeInfo {
+        <XgBitVec as Type<Xugu>>::type_info()
+    }
+
+    fn compatible(ty: &XuguTypeInfo) -> bool {
+        <XgBitVec as Type<Xugu>>::compatible(ty)
+    }
+}
+
+impl Encode<'_, Xugu> for BitVec {
+    fn encode_by_ref(&self, args: &mut Vec<XuguArgumentValue>) -> Result<IsNull, BoxDynError> {
+        <XgBitVec as Encode<Xugu>>::encode(XgBitVec::from(self), args)
+    }
+
+    fn produces(&self) -> Option<XuguTypeInfo> {
+        <XgBitVec as Encode<Xugu>>::produces(&XgBitVec::new())
+    }
+}
+
+impl<'r> Decode<'r, Xugu> for BitVec {
+    fn decode(value: XuguValueRef<'r>) -> Result<Self, BoxDynError> {
+        <XgBitVec as Decode<Xugu>>::decode(value).map(BitVec::from)
+    }
+}
@@ -1,5 +1,6 @@
 use crate::arguments::XuguArgumentValue;
 use crate::protocol::text::ColumnType;
+use crate::types::XgInterval;
 use crate::{Xugu, XuguTypeInfo, XuguValueRef};
 use bytes::Buf;
 use chrono::TimeDelta;
@@ -96,3 +97,28 @@ impl<'r> Decode<'r, Xugu> for TimeDelta {
         }
     }
 }
+
+impl TryFrom<TimeDelta> for XgInterval {
+    type Error = BoxDynError;
+
+    fn try_from(value: TimeDelta) -> Result<Self, Self::Error> {
+        let micros = value
+            .num_microseconds()
+            .ok_or_else(|| format!("value {value:?} overflows Xugu INTERVAL DAY TO SECOND"))?;
+
+        Ok(XgInterval { months: 0, micros })
+    }
+}
+
+/// 年-月间隔的天数不固定，无法转换
+impl TryFrom<XgInterval> for TimeDelta {
+    type Error = BoxDynError;
+
+    fn try_from(value: XgInterval) -> Result<Self, Self::Error> {
+        if value.months != 0 {
+            return Err(format!("interval {value} has a year-month part").into());
+        }
+
+        Ok(TimeDelta::microseconds(value.micros))
+    }
+}
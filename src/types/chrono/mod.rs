@@ -2,6 +2,8 @@ mod interval;
 
 use crate::arguments::XuguArgumentValue;
 use crate::protocol::text::ColumnType;
+use crate::types::array::{array_type, non_null};
+use crate::types::XuguHasArrayType;
 use crate::{Xugu, XuguTypeInfo, XuguValueRef};
 use bytes::{Buf, BufMut};
 use chrono::{
@@ -59,7 +61,13 @@ impl<'r> Decode<'r, Xugu> for DateTime<FixedOffset> {
         let ty = value.type_info.r#type;
         // 不带时区的日期时间
         if ty == ColumnType::DATETIME || ty == ColumnType::DATE {
+            let time_zone = value.time_zone;
             let native = <NaiveDateTime as Decode<Xugu>>::decode(value)?;
+            // 优先使用会话时区，未知时使用本地时区
+            if let Some(offset) = time_zone.and_then(FixedOffset::east_opt) {
+                let tz = native.and_local_timezone(offset).unwrap();
+                return Ok(tz);
+            }
             let local = native.and_local_timezone(Local).unwrap();
             let tz = local.fixed_offset();
 
@@ -207,3 +215,158 @@ impl<'r> Decode<'r, Xugu> for NaiveDate {
         Ok(date)
     }
 }
+
+impl XuguHasArrayType for NaiveDate {
+    fn array_type_info() -> XuguTypeInfo {
+        array_type(ColumnType::ARRAY_DATE)
+    }
+
+    fn encode_element(&self) -> Result<Option<String>, BoxDynError> {
+        Ok(Some(self.format("%Y-%m-%d").to_string()))
+    }
+
+    fn decode_element(text: Option<&str>) -> Result<Self, BoxDynError> {
+        Ok(NaiveDate::parse_from_str(
+            non_null(text)?.trim(),
+            "%Y-%m-%d",
+        )?)
+    }
+}
+
+impl XuguHasArrayType for NaiveTime {
+    fn array_type_info() -> XuguTypeInfo {
+        array_type(ColumnType::ARRAY_TIME)
+    }
+
+    fn encode_element(&self) -> Result<Option<String>, BoxDynError> {
+        Ok(Some(self.format("%H:%M:%S%.f").to_string()))
+    }
+
+    fn decode_element(text: Option<&str>) -> Result<Self, BoxDynError> {
+        Ok(NaiveTime::parse_from_str(
+            non_null(text)?.trim(),
+            "%H:%M:%S%.f",
+        )?)
+    }
+}
+
+impl XuguHasArrayType for NaiveDateTime {
+    fn array_type_info() -> XuguTypeInfo {
+        array_type(ColumnType::ARRAY_DATETIME)
+    }
+
+    fn encode_element(&self) -> Result<Option<String>, BoxDynError> {
+        Ok(Some(self.format("%Y-%m-%d %H:%M:%S%.f").to_string()))
+    }
+
+    fn decode_element(text: Option<&str>) -> Result<Self, BoxDynError> {
+        let text = non_null(text)?.trim();
+        NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S%.f")
+            .or_else(|_| NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M:%S%.f"))
+            .map_err(Into::into)
+    }
+}
+
+/// 带时区的日期时间数组元素，如 `2024-01-02 03:04:05.123456 +08:00`
+fn encode_datetime_tz<Tz: TimeZone>(value: &DateTime<Tz>) -> String {
+    value
+        .fixed_offset()
+        .format("%Y-%m-%d %H:%M:%S%.f %:z")
+        .to_string()
+}
+
+/// 日期和时间之间可以是空格或 `T`，时区前的空格可以省略，时区可以省略分钟
+fn decode_datetime_tz(text: Option<&str>) -> Result<DateTime<FixedOffset>, BoxDynError> {
+    let text = non_null(text)?.trim();
+    const FORMATS: [&str; 4] = [
+        "%Y-%m-%d %H:%M:%S%.f %#z",
+        "%Y-%m-%d %H:%M:%S%.f%#z",
+        "%Y-%m-%dT%H:%M:%S%.f %#z",
+        "%Y-%m-%dT%H:%M:%S%.f%#z",
+    ];
+    FORMATS
+        .iter()
+        .find_map(|format| DateTime::parse_from_str(text, format).ok())
+        .ok_or_else(|| format!("invalid datetime with time zone {text:?}").into())
+}
+
+impl XuguHasArrayType for DateTime<FixedOffset> {
+    fn array_type_info() -> XuguTypeInfo {
+        array_type(ColumnType::ARRAY_DATETIME_TZ)
+    }
+
+    fn encode_element(&self) -> Result<Option<String>, BoxDynError> {
+        Ok(Some(encode_datetime_tz(self)))
+    }
+
+    fn decode_element(text: Option<&str>) -> Result<Self, BoxDynError> {
+        decode_datetime_tz(text)
+    }
+}
+
+impl XuguHasArrayType for DateTime<Utc> {
+    fn array_type_info() -> XuguTypeInfo {
+        array_type(ColumnType::ARRAY_DATETIME_TZ)
+    }
+
+    fn encode_element(&self) -> Result<Option<String>, BoxDynError> {
+        Ok(Some(encode_datetime_tz(self)))
+    }
+
+    fn decode_element(text: Option<&str>) -> Result<Self, BoxDynError> {
+        decode_datetime_tz(text).map(DateTime::from)
+    }
+}
+
+impl XuguHasArrayType for DateTime<Local> {
+    fn array_type_info() -> XuguTypeInfo {
+        array_type(ColumnType::ARRAY_DATETIME_TZ)
+    }
+
+    fn encode_element(&self) -> Result<Option<String>, BoxDynError> {
+        Ok(Some(encode_datetime_tz(self)))
+    }
+
+    fn decode_element(text: Option<&str>) -> Result<Self, BoxDynError> {
+        decode_datetime_tz(text).map(DateTime::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::array::{encode_array, parse_array};
+    use chrono::Timelike;
+
+    #[test]
+    fn datetime_tz_array_elements() {
+        let offset = FixedOffset::east_opt(8 * 3600).unwrap();
+        let value = offset
+            .with_ymd_and_hms(2024, 1, 2, 3, 4, 5)
+            .unwrap()
+            .with_nanosecond(123_456_000)
+            .unwrap();
+
+        let text = encode_array(&[Some(value), None]).unwrap();
+        assert_eq!(text, r#"{"2024-01-02 03:04:05.123456 +08:00",NULL}"#);
+        let elements = parse_array(&text).unwrap();
+        let decoded = DateTime::<FixedOffset>::decode_element(elements[0].as_deref()).unwrap();
+        assert_eq!(decoded, value);
+        assert_eq!(decoded.offset(), &offset);
+
+        let utc = DateTime::<Utc>::decode_element(Some("2024-01-01T19:04:05.123456+00")).unwrap();
+        assert_eq!(utc, value);
+        for text in [
+            "2024-01-02 03:04:05.123456+0800",
+            "2024-01-02 03:04:05.123456 +08",
+        ] {
+            assert_eq!(DateTime::<Utc>::decode_element(Some(text)).unwrap(), value);
+        }
+
+        assert!(DateTime::<Utc>::decode_element(Some("2024-01-02 03:04:05")).is_err());
+        assert_eq!(
+            <Vec<DateTime<Local>> as Type<Xugu>>::type_info().r#type,
+            ColumnType::ARRAY_DATETIME_TZ
+        );
+    }
+}
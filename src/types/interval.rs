@@ -0,0 +1,676 @@
+use crate::arguments::XuguArgumentValue;
+use crate::protocol::text::ColumnType;
+use crate::{Xugu, XuguTypeInfo, XuguValueRef};
+use bytes::Buf;
+use sqlx_core::decode::Decode;
+use sqlx_core::encode::{Encode, IsNull};
+use sqlx_core::error::BoxDynError;
+use sqlx_core::types::Type;
+use std::borrow::Cow;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+const MICROS_PER_SECOND: i64 = 1_000_000;
+const MICROS_PER_MINUTE: i64 = 60 * MICROS_PER_SECOND;
+const MICROS_PER_HOUR: i64 = 60 * MICROS_PER_MINUTE;
+const MICROS_PER_DAY: i64 = 24 * MICROS_PER_HOUR;
+
+/// ## Xugu INTERVAL type
+///
+/// 时间间隔，可以表示所有 `INTERVAL_*` 类型。
+///
+/// 虚谷的时间间隔分为两类，不能混用：
+/// * 年-月间隔（`INTERVAL YEAR`、`INTERVAL YEAR TO MONTH`、`INTERVAL MONTH`），使用 `months` 保存
+/// * 日-时间间隔（`INTERVAL DAY` 至 `INTERVAL SECOND`），使用 `micros` 保存
+///
+/// 文本表示为虚谷的时间间隔字面量，如 `INTERVAL '1-2' YEAR TO MONTH`、
+/// `INTERVAL '3 04:05:06.789' DAY TO SECOND`。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct XgInterval {
+    /// 月数
+    pub months: i32,
+    /// 微秒数
+    pub micros: i64,
+}
+
+impl XgInterval {
+    /// 年-月间隔，月数超出 `i32` 时返回 `None`
+    pub const fn year_month(years: i32, months: i32) -> Option<Self> {
+        let Some(years) = years.checked_mul(12) else {
+            return None;
+        };
+        match years.checked_add(months) {
+            Some(months) => Some(Self { months, micros: 0 }),
+            None => None,
+        }
+    }
+
+    /// 日-时间间隔，微秒数超出 `i64` 时返回 `None`
+    pub const fn day_time(
+        days: i32,
+        hours: i32,
+        minutes: i32,
+        seconds: i32,
+        micros: i64,
+    ) -> Option<Self> {
+        // 天数换算为微秒时就可能超出 i64，使用 i128 计算
+        let total = days as i128 * MICROS_PER_DAY as i128
+            + hours as i128 * MICROS_PER_HOUR as i128
+            + minutes as i128 * MICROS_PER_MINUTE as i128
+            + seconds as i128 * MICROS_PER_SECOND as i128
+            + micros as i128;
+        if total < i64::MIN as i128 || total > i64::MAX as i128 {
+            return None;
+        }
+
+        Some(Self {
+            months: 0,
+            micros: total as i64,
+        })
+    }
+
+    /// 是否为年-月间隔
+    pub const fn is_year_month(&self) -> bool {
+        self.months != 0 && self.micros == 0
+    }
+
+    /// 是否为日-时间间隔，零间隔也视为日-时间间隔
+    pub const fn is_day_time(&self) -> bool {
+        self.months == 0
+    }
+
+    /// 编码时使用的列类型
+    fn column_type(&self) -> Result<ColumnType, BoxDynError> {
+        match (self.months, self.micros) {
+            (0, _) => Ok(ColumnType::INTERVAL_D2S),
+            (_, 0) => Ok(ColumnType::INTERVAL_Y2M),
+            _ => Err(format!(
+                "interval {self} mixes months and a day-time part, which Xugu cannot store in one column"
+            )
+            .into()),
+        }
+    }
+
+    fn from_micros(micros: Option<i64>) -> Result<Self, BoxDynError> {
+        micros
+            .map(|micros| Self { months: 0, micros })
+            .ok_or_else(|| "interval overflows i64 microseconds".into())
+    }
+}
+
+impl Type<Xugu> for XgInterval {
+    fn type_info() -> XuguTypeInfo {
+        XuguTypeInfo::binary(ColumnType::INTERVAL_D2S)
+    }
+
+    fn compatible(ty: &XuguTypeInfo) -> bool {
+        matches!(
+            ty.r#type,
+            ColumnType::INTERVAL_Y
+                | ColumnType::INTERVAL_Y2M
+                | ColumnType::INTERVAL_M
+                | ColumnType::INTERVAL_D
+                | ColumnType::INTERVAL_D2H
+                | ColumnType::INTERVAL_D2M
+                | ColumnType::INTERVAL_D2S
+                | ColumnType::INTERVAL_H
+                | ColumnType::INTERVAL_H2M
+                | ColumnType::INTERVAL_H2S
+                | ColumnType::INTERVAL_MI
+                | ColumnType::INTERVAL_M2S
+                | ColumnType::INTERVAL_S
+        )
+    }
+}
+
+/// 年-月间隔按 `INTERVAL_Y2M`（4 字节月数）编码，日-时间间隔按 `INTERVAL_D2S`（8 字节微秒数）编码
+impl Encode<'_, Xugu> for XgInterval {
+    fn encode_by_ref(&self, args: &mut Vec<XuguArgumentValue>) -> Result<IsNull, BoxDynError> {
+        let buf = match self.column_type()? {
+            ColumnType::INTERVAL_Y2M => self.months.to_be_bytes().to_vec(),
+            _ => self.micros.to_be_bytes().to_vec(),
+        };
+        args.push(XuguArgumentValue::Bin(Cow::Owned(buf)));
+
+        Ok(IsNull::No)
+    }
+
+    fn produces(&self) -> Option<XuguTypeInfo> {
+        let ty = self.column_type().unwrap_or(ColumnType::INTERVAL_D2S);
+        Some(XuguTypeInfo::binary(ty))
+    }
+}
+
+impl<'r> Decode<'r, Xugu> for XgInterval {
+    fn decode(value: XuguValueRef<'r>) -> Result<Self, BoxDynError> {
+        let ty = value.type_info.r#type;
+        let mut buf = value.as_bytes()?;
+        let expected = match ty {
+            ColumnType::INTERVAL_D2S
+            | ColumnType::INTERVAL_H2S
+            | ColumnType::INTERVAL_M2S
+            | ColumnType::INTERVAL_S => 8,
+            _ => 4,
+        };
+        if buf.len() != expected {
+            return Err(format!(
+                "expected {expected} bytes for {ty:?}, got {} bytes",
+                buf.len()
+            )
+            .into());
+        }
+
+        match ty {
+            // 精确到年
+            ColumnType::INTERVAL_Y => {
+                let years = buf.get_i32();
+                let months = years
+                    .checked_mul(12)
+                    .ok_or("interval overflows i32 months")?;
+                Ok(Self { months, micros: 0 })
+            }
+            // 精确到月
+            ColumnType::INTERVAL_Y2M | ColumnType::INTERVAL_M => Ok(Self {
+                months: buf.get_i32(),
+                micros: 0,
+            }),
+            // 精确到天
+            ColumnType::INTERVAL_D => {
+                Self::from_micros((buf.get_i32() as i64).checked_mul(MICROS_PER_DAY))
+            }
+            // 精确到小时
+            ColumnType::INTERVAL_D2H | ColumnType::INTERVAL_H => {
+                Self::from_micros((buf.get_i32() as i64).checked_mul(MICROS_PER_HOUR))
+            }
+            // 精确到分钟
+            ColumnType::INTERVAL_D2M | ColumnType::INTERVAL_H2M | ColumnType::INTERVAL_MI => {
+                Self::from_micros((buf.get_i32() as i64).checked_mul(MICROS_PER_MINUTE))
+            }
+            // 精确到秒
+            ColumnType::INTERVAL_D2S
+            | ColumnType::INTERVAL_H2S
+            | ColumnType::INTERVAL_M2S
+            | ColumnType::INTERVAL_S => Self::from_micros(Some(buf.get_i64())),
+            _ => Err(BoxDynError::from(
+                "[E50044] Resultset: Required type conversion not allowed",
+            )),
+        }
+    }
+}
+
+impl Display for XgInterval {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.months != 0 && self.micros == 0 {
+            let sign = if self.months < 0 { "-" } else { "" };
+            let months = self.months.unsigned_abs();
+            return write!(
+                f,
+                "INTERVAL '{sign}{}-{}' YEAR TO MONTH",
+                months / 12,
+                months % 12
+            );
+        }
+
+        if self.months != 0 {
+            // 无法用一个字面量表示，按两个字面量相加输出，`FromStr` 可以解析这种形式
+            let year_month = Self {
+                months: self.months,
+                micros: 0,
+            };
+            let day_time = Self {
+                months: 0,
+                micros: self.micros,
+            };
+            return write!(f, "{year_month} + {day_time}");
+        }
+
+        let sign = if self.micros < 0 { "-" } else { "" };
+        let micros = self.micros.unsigned_abs();
+        let days = micros / MICROS_PER_DAY as u64;
+        let hours = micros / MICROS_PER_HOUR as u64 % 24;
+        let minutes = micros / MICROS_PER_MINUTE as u64 % 60;
+        let seconds = micros / MICROS_PER_SECOND as u64 % 60;
+        let fraction = micros % MICROS_PER_SECOND as u64;
+
+        write!(
+            f,
+            "INTERVAL '{sign}{days} {hours:02}:{minutes:02}:{seconds:02}"
+        )?;
+        if fraction != 0 {
+            let fraction = format!("{fraction:06}");
+            write!(f, ".{}", fraction.trim_end_matches('0'))?;
+        }
+        f.write_str("' DAY TO SECOND")
+    }
+}
+
+/// 时间间隔字面量中的字段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Field {
+    Year,
+    Month,
+    Day,
+    Hour,
+    Minute,
+    Second,
+}
+
+impl Field {
+    fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_uppercase().as_str() {
+            "YEAR" => Some(Field::Year),
+            "MONTH" => Some(Field::Month),
+            "DAY" => Some(Field::Day),
+            "HOUR" => Some(Field::Hour),
+            "MINUTE" => Some(Field::Minute),
+            "SECOND" => Some(Field::Second),
+            _ => None,
+        }
+    }
+
+    fn micros(self) -> i64 {
+        match self {
+            Field::Day => MICROS_PER_DAY,
+            Field::Hour => MICROS_PER_HOUR,
+            Field::Minute => MICROS_PER_MINUTE,
+            _ => MICROS_PER_SECOND,
+        }
+    }
+}
+
+/// 解析时间间隔字面量，如 `INTERVAL '1-2' YEAR TO MONTH`，关键字 `INTERVAL` 可以省略
+///
+/// 支持所有 `INTERVAL_*` 类型的字段组合，数值前可以带符号，秒最多精确到微秒。
+/// 同时包含两类间隔的值按 [`Display`] 输出的形式解析，即年-月字面量加日-时间字面量，
+/// 如 `INTERVAL '1-2' YEAR TO MONTH + INTERVAL '3 00:00:00' DAY TO SECOND`。
+impl FromStr for XgInterval {
+    type Err = BoxDynError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((year_month, day_time)) = split_sum(s) else {
+            return parse_literal(s);
+        };
+
+        let year_month = parse_literal(year_month)?;
+        let day_time = parse_literal(day_time)?;
+        if year_month.micros != 0 || day_time.months != 0 {
+            return Err(format!(
+                "invalid interval literal {s:?}: expected a year-month interval plus a day-time interval"
+            )
+            .into());
+        }
+
+        Ok(Self {
+            months: year_month.months,
+            micros: day_time.micros,
+        })
+    }
+}
+
+/// 在引号外的第一个 `+` 处拆分
+fn split_sum(s: &str) -> Option<(&str, &str)> {
+    let mut quoted = false;
+    for (i, c) in s.char_indices() {
+        match c {
+            '\'' => quoted = !quoted,
+            '+' if !quoted => return Some((&s[..i], &s[i + 1..])),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// 解析单个时间间隔字面量
+fn parse_literal(s: &str) -> Result<XgInterval, BoxDynError> {
+    let invalid = || -> BoxDynError { format!("invalid interval literal {s:?}").into() };
+
+    let text = s.trim();
+    let text = match text.get(..8) {
+        Some(keyword) if keyword.eq_ignore_ascii_case("INTERVAL") => text[8..].trim_start(),
+        _ => text,
+    };
+    let (value, qualifier) = text
+        .strip_prefix('\'')
+        .and_then(|s| s.split_once('\''))
+        .ok_or_else(invalid)?;
+
+    let qualifier: Vec<&str> = qualifier.split_whitespace().collect();
+    let (from, to) = match qualifier.as_slice() {
+        [from] => (Field::parse(from), Field::parse(from)),
+        [from, keyword, to] if keyword.eq_ignore_ascii_case("TO") => {
+            (Field::parse(from), Field::parse(to))
+        }
+        _ => return Err(invalid()),
+    };
+    let (Some(from), Some(to)) = (from, to) else {
+        return Err(invalid());
+    };
+
+    let value = value.trim();
+    let (sign, value) = match value.strip_prefix('-') {
+        Some(value) => (-1, value),
+        None => (1, value.strip_prefix('+').unwrap_or(value)),
+    };
+
+    // 先按绝对值以 i128 计算，带上符号后再检查范围，`i32::MIN` 个月等负数边界也能解析
+    let (months, micros): (i128, i128) = match (from, to) {
+        (Field::Year, Field::Month) => {
+            let (years, months) = value.split_once('-').ok_or_else(invalid)?;
+            let years: u32 = years.trim().parse().map_err(|_| invalid())?;
+            let months: u32 = months.trim().parse().map_err(|_| invalid())?;
+            if months >= 12 {
+                return Err(invalid());
+            }
+            (years as i128 * 12 + months as i128, 0)
+        }
+        (Field::Year, Field::Year) | (Field::Month, Field::Month) => {
+            let n: u32 = value.parse().map_err(|_| invalid())?;
+            let factor = if from == Field::Year { 12 } else { 1 };
+            (n as i128 * factor, 0)
+        }
+        (from, to) if from >= Field::Day && from <= to => {
+            let parts: Vec<&str> = value.split([' ', ':']).collect();
+            let fields = [Field::Day, Field::Hour, Field::Minute, Field::Second];
+            let fields: Vec<Field> = fields
+                .into_iter()
+                .filter(|field| *field >= from && *field <= to)
+                .collect();
+            if parts.len() != fields.len() {
+                return Err(invalid());
+            }
+
+            let mut micros: i128 = 0;
+            for (part, field) in parts.into_iter().zip(fields) {
+                micros += if field == Field::Second {
+                    parse_seconds(part).ok_or_else(invalid)? as i128
+                } else {
+                    let n: u64 = part.parse().map_err(|_| invalid())?;
+                    n as i128 * field.micros() as i128
+                };
+            }
+            (0, micros)
+        }
+        _ => return Err(invalid()),
+    };
+
+    Ok(XgInterval {
+        months: i32::try_from(sign * months).map_err(|_| invalid())?,
+        micros: i64::try_from(sign * micros).map_err(|_| invalid())?,
+    })
+}
+
+/// 解析 `ss[.ffffff]`，返回微秒数
+fn parse_seconds(s: &str) -> Option<u64> {
+    let (seconds, fraction) = s.split_once('.').unwrap_or((s, ""));
+    if fraction.len() > 6 || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let seconds: u64 = seconds.parse().ok()?;
+    let fraction: u64 = format!("{fraction:0<6}").parse().ok()?;
+    seconds
+        .checked_mul(MICROS_PER_SECOND as u64)?
+        .checked_add(fraction)
+}
+
+impl TryFrom<std::time::Duration> for XgInterval {
+    type Error = BoxDynError;
+
+    fn try_from(value: std::time::Duration) -> Result<Self, Self::Error> {
+        let micros = value
+            .as_micros()
+            .try_into()
+            .map_err(|_| format!("value {value:?} overflows Xugu INTERVAL DAY TO SECOND"))?;
+
+        Ok(Self { months: 0, micros })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constructors_check_overflow() {
+        assert_eq!(
+            XgInterval::year_month(1, 2),
+            Some(XgInterval {
+                months: 14,
+                micros: 0
+            })
+        );
+        assert_eq!(XgInterval::year_month(i32::MAX, 0), None);
+        assert_eq!(XgInterval::year_month(i32::MAX / 12, 12), None);
+        assert_eq!(
+            XgInterval::year_month(i32::MIN / 12, -8).unwrap().months,
+            i32::MIN
+        );
+
+        assert_eq!(
+            XgInterval::day_time(1, 2, 3, 4, 5).unwrap().micros,
+            MICROS_PER_DAY
+                + 2 * MICROS_PER_HOUR
+                + 3 * MICROS_PER_MINUTE
+                + 4 * MICROS_PER_SECOND
+                + 5
+        );
+        assert_eq!(XgInterval::day_time(i32::MAX, 0, 0, 0, 0), None);
+        assert_eq!(XgInterval::day_time(0, 0, 0, 1, i64::MAX), None);
+        assert_eq!(
+            XgInterval::day_time(0, 0, 0, -1, i64::MIN + MICROS_PER_SECOND)
+                .unwrap()
+                .micros,
+            i64::MIN
+        );
+    }
+
+    #[test]
+    fn display_round_trip() {
+        let values = [
+            (0, 0),
+            (14, 0),
+            (-14, 0),
+            (i32::MAX, 0),
+            (i32::MIN, 0),
+            (0, 1),
+            (0, -MICROS_PER_DAY - 1),
+            (0, i64::MAX),
+            (0, i64::MIN),
+            (14, 3 * MICROS_PER_DAY),
+            (-14, 1),
+            (1, -MICROS_PER_SECOND / 2),
+            (i32::MIN, i64::MIN),
+        ];
+        for (months, micros) in values {
+            let interval = XgInterval { months, micros };
+            let text = interval.to_string();
+            assert_eq!(text.parse::<XgInterval>().unwrap(), interval, "{text}");
+        }
+
+        assert_eq!(
+            XgInterval {
+                months: -14,
+                micros: 1
+            }
+            .to_string(),
+            "INTERVAL '-1-2' YEAR TO MONTH + INTERVAL '0 00:00:00.000001' DAY TO SECOND"
+        );
+    }
+
+    #[test]
+    fn parse_literals() {
+        let cases = [
+            ("INTERVAL '1-2' YEAR TO MONTH", 14, 0),
+            ("'-3' YEAR", -36, 0),
+            ("interval '+5' month", 5, 0),
+            (
+                "INTERVAL '1 02' DAY TO HOUR",
+                0,
+                MICROS_PER_DAY + 2 * MICROS_PER_HOUR,
+            ),
+            ("INTERVAL '-90' MINUTE", 0, -90 * MICROS_PER_MINUTE),
+            ("INTERVAL '1:02.5' MINUTE TO SECOND", 0, 62_500_000),
+            ("'1-0' YEAR TO MONTH+'0 00:00:00' DAY TO SECOND", 12, 0),
+        ];
+        for (text, months, micros) in cases {
+            assert_eq!(
+                text.parse::<XgInterval>().unwrap(),
+                XgInterval { months, micros },
+                "{text}"
+            );
+        }
+    }
+
+    #[test]
+    fn parse_rejects_invalid() {
+        for text in [
+            "",
+            "INTERVAL '1' WEEK",
+            "INTERVAL '1-12' YEAR TO MONTH",
+            "INTERVAL '1--2' YEAR TO MONTH",
+            "INTERVAL '1 -2' DAY TO HOUR",
+            "INTERVAL '1' MONTH TO YEAR",
+            "INTERVAL '1.1234567' SECOND",
+            "INTERVAL '2147483648' MONTH",
+            "INTERVAL '-2147483649' MONTH",
+            "INTERVAL '106751992' DAY",
+            // 两部分必须分别是年-月和日-时间间隔
+            "INTERVAL '1' YEAR + INTERVAL '2' YEAR",
+            "INTERVAL '1' DAY + INTERVAL '2' DAY",
+            "INTERVAL '1' DAY + INTERVAL '1' YEAR",
+            "INTERVAL '1' YEAR + INTERVAL '1' DAY + INTERVAL '1' DAY",
+            "INTERVAL '1' YEAR +",
+        ] {
+            assert!(text.parse::<XgInterval>().is_err(), "{text}");
+        }
+    }
+
+    fn encode(value: XgInterval) -> Result<(ColumnType, Vec<u8>), BoxDynError> {
+        let mut args = Vec::new();
+        assert!(matches!(value.encode_by_ref(&mut args)?, IsNull::No));
+        let ty = value.produces().unwrap().r#type;
+        match args.as_slice() {
+            [XuguArgumentValue::Bin(buf)] => Ok((ty, buf.to_vec())),
+            _ => panic!("unexpected arguments {args:?}"),
+        }
+    }
+
+    fn decode(ty: ColumnType, bytes: &[u8]) -> Result<XgInterval, BoxDynError> {
+        XgInterval::decode(XuguValueRef {
+            value: Some(bytes),
+            row: None,
+            type_info: XuguTypeInfo {
+                r#type: ty,
+                flags: crate::protocol::text::ColumnFlags::empty(),
+            },
+            time_zone: None,
+        })
+    }
+
+    #[test]
+    fn encode_bytes() {
+        // 年-月间隔：4 字节大端序月数
+        assert_eq!(
+            encode(XgInterval::year_month(1, 2).unwrap()).unwrap(),
+            (ColumnType::INTERVAL_Y2M, vec![0, 0, 0, 14])
+        );
+        assert_eq!(
+            encode(XgInterval::year_month(0, -1).unwrap()).unwrap(),
+            (ColumnType::INTERVAL_Y2M, vec![0xff, 0xff, 0xff, 0xff])
+        );
+
+        // 日-时间间隔：8 字节大端序微秒数
+        assert_eq!(
+            encode(XgInterval::day_time(0, 0, 0, 1, 2).unwrap()).unwrap(),
+            (
+                ColumnType::INTERVAL_D2S,
+                vec![0, 0, 0, 0, 0, 0x0f, 0x42, 0x42]
+            )
+        );
+        assert_eq!(
+            encode(XgInterval::day_time(0, 0, 0, 0, -2).unwrap()).unwrap(),
+            (
+                ColumnType::INTERVAL_D2S,
+                vec![0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfe]
+            )
+        );
+        assert_eq!(
+            encode(XgInterval::default()).unwrap(),
+            (ColumnType::INTERVAL_D2S, vec![0; 8])
+        );
+
+        // 同时包含月数和日-时间部分
+        let mixed = XgInterval {
+            months: 1,
+            micros: 1,
+        };
+        let err = encode(mixed).unwrap_err();
+        assert!(err.to_string().contains("mixes months"), "{err}");
+    }
+
+    #[test]
+    fn decode_bytes() {
+        let four = |n: i32| n.to_be_bytes().to_vec();
+        let eight = |n: i64| n.to_be_bytes().to_vec();
+        let months = |months| XgInterval { months, micros: 0 };
+        let micros = |micros| XgInterval { months: 0, micros };
+
+        let cases = [
+            (ColumnType::INTERVAL_Y, four(2), months(24)),
+            (ColumnType::INTERVAL_Y, four(-1), months(-12)),
+            (ColumnType::INTERVAL_Y2M, four(14), months(14)),
+            (ColumnType::INTERVAL_Y2M, four(-14), months(-14)),
+            (ColumnType::INTERVAL_M, four(-3), months(-3)),
+            (ColumnType::INTERVAL_D, four(2), micros(2 * MICROS_PER_DAY)),
+            (
+                ColumnType::INTERVAL_D,
+                four(-2),
+                micros(-2 * MICROS_PER_DAY),
+            ),
+            (
+                ColumnType::INTERVAL_D2H,
+                four(25),
+                micros(25 * MICROS_PER_HOUR),
+            ),
+            (
+                ColumnType::INTERVAL_H,
+                four(-5),
+                micros(-5 * MICROS_PER_HOUR),
+            ),
+            (
+                ColumnType::INTERVAL_D2M,
+                four(61),
+                micros(61 * MICROS_PER_MINUTE),
+            ),
+            (
+                ColumnType::INTERVAL_H2M,
+                four(-61),
+                micros(-61 * MICROS_PER_MINUTE),
+            ),
+            (
+                ColumnType::INTERVAL_MI,
+                four(7),
+                micros(7 * MICROS_PER_MINUTE),
+            ),
+            (
+                ColumnType::INTERVAL_D2S,
+                eight(1_000_002),
+                micros(1_000_002),
+            ),
+            (ColumnType::INTERVAL_H2S, eight(-1), micros(-1)),
+            (ColumnType::INTERVAL_M2S, eight(i64::MAX), micros(i64::MAX)),
+            (ColumnType::INTERVAL_S, eight(i64::MIN), micros(i64::MIN)),
+        ];
+        for (ty, bytes, expected) in cases {
+            assert_eq!(decode(ty, &bytes).unwrap(), expected, "{ty:?} {bytes:?}");
+        }
+
+        // 长度与列类型不符
+        assert!(decode(ColumnType::INTERVAL_Y2M, &eight(1)).is_err());
+        assert!(decode(ColumnType::INTERVAL_D2S, &four(1)).is_err());
+        // 换算后溢出
+        assert!(decode(ColumnType::INTERVAL_Y, &four(i32::MAX)).is_err());
+        assert!(decode(ColumnType::INTERVAL_D, &four(i32::MIN)).is_err());
+        assert!(decode(ColumnType::INTEGER, &four(1)).is_err());
+    }
+}
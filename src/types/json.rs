@@ -2,6 +2,9 @@ use crate::arguments::XuguArgumentValue;
 use crate::protocol::text::ColumnType;
 use crate::{Xugu, XuguTypeInfo, XuguValueRef};
 
+use crate::types::array::{array_type, non_null};
+use crate::types::XuguHasArrayType;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use sqlx_core::decode::Decode;
 use sqlx_core::encode::{Encode, IsNull};
@@ -39,3 +42,50 @@ where
         Self::decode_from_string(Decode::<Xugu>::decode(value)?)
     }
 }
+
+/// `ARRAY_JSON` 的元素，按 JSON 文本编解码
+impl<T> XuguHasArrayType for Json<T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    fn array_type_info() -> XuguTypeInfo {
+        array_type(ColumnType::ARRAY_JSON)
+    }
+
+    fn encode_element(&self) -> Result<Option<String>, BoxDynError> {
+        Ok(Some(self.encode_to_string()?))
+    }
+
+    fn decode_element(text: Option<&str>) -> Result<Self, BoxDynError> {
+        Json::decode_from_string(non_null(text)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::array::{encode_array, parse_array};
+    use sqlx_core::types::JsonValue;
+
+    #[test]
+    fn json_array_round_trip() {
+        let values = [r#"{"a":[1,2],"b":"x,y"}"#, "null", r#""say \"hi\"""#]
+            .map(|json| Json::<JsonValue>::decode_from_string(json).unwrap());
+        let text = encode_array(&values).unwrap();
+        assert_eq!(
+            text,
+            r#"{"{\"a\":[1,2],\"b\":\"x,y\"}","null","\"say \\\"hi\\\"\""}"#
+        );
+
+        let decoded = parse_array(&text)
+            .unwrap()
+            .iter()
+            .map(|element| Json::<JsonValue>::decode_element(element.as_deref()))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(decoded, values);
+
+        assert!(Json::<JsonValue>::decode_element(Some("{bad")).is_err());
+        assert!(Json::<JsonValue>::decode_element(None).is_err());
+    }
+}
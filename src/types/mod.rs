@@ -1,15 +1,27 @@
+mod array;
+mod bit;
 mod bool;
 mod bytes;
 mod float;
 mod int;
+mod interval;
+mod numeric;
+mod record;
 mod std_duration;
 mod str;
 mod text;
 mod uint;
+mod xml;
 
 /// 简单空间类型
 mod geometry;
 
+/// OGC 空间类型
+mod spatial;
+
+/// 向量类型
+mod vector;
+
 #[cfg(feature = "json")]
 mod json;
 
@@ -28,4 +40,16 @@ mod time;
 #[cfg(feature = "uuid")]
 mod uuid;
 
+#[cfg(feature = "bit-vec")]
+mod bit_vec;
+
+pub use array::XuguHasArrayType;
+pub use bit::XgBitVec;
 pub use geometry::*;
+pub use interval::XgInterval;
+pub(crate) use numeric::fit_numeric;
+pub use numeric::XgNumeric;
+pub use record::{XuguRecordDecoder, XuguRecordEncoder};
+pub use spatial::{XgCoord, XgGeometry, XgShape};
+pub use vector::*;
+pub use xml::XgXml;
@@ -0,0 +1,740 @@
+use crate::arguments::XuguArgumentValue;
+use crate::protocol::text::{ColumnFlags, ColumnType};
+use crate::types::array::impl_has_array_type;
+use crate::{Xugu, XuguNumericRounding, XuguTypeInfo, XuguValueRef};
+use num_bigint::BigInt;
+use sqlx_core::decode::Decode;
+use sqlx_core::encode::{Encode, IsNull};
+use sqlx_core::error::BoxDynError;
+use sqlx_core::types::Type;
+use std::borrow::Cow;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// ## Xugu NUMERIC type
+///
+/// 任意精度的十进制数，原样保存服务器返回的文本（包括小数部分末尾的 0），
+/// 不依赖 `rust_decimal` 或 `bigdecimal`。
+///
+/// 可以无损地与 `i128`、`u64`、`i64` 和 [`BigInt`] 相互转换（小数部分不为 0 时转换为整数会返回错误）；
+/// 由 `f64` 转换时使用能精确还原该 `f64` 的最短十进制表示。
+///
+/// ```rust
+/// # use sqlx_xugu::types::XgNumeric;
+/// let n: XgNumeric = "-12345678901234567890.500".parse().unwrap();
+/// assert_eq!(n.scale(), 3);
+/// assert_eq!(n.precision(), 23);
+/// assert_eq!(n.to_string(), "-12345678901234567890.500");
+/// assert!(i128::try_from(&n).is_err());
+///
+/// let id = XgNumeric::from(u64::MAX);
+/// assert_eq!(u64::try_from(&id).unwrap(), u64::MAX);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct XgNumeric {
+    text: String,
+}
+
+impl XgNumeric {
+    /// 原始文本
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    fn parts(&self) -> (bool, &str, &str) {
+        split(&self.text).expect("XgNumeric holds a valid number")
+    }
+
+    /// 小数位数
+    pub fn scale(&self) -> u32 {
+        self.parts().2.len() as u32
+    }
+
+    /// 有效数字的位数，不包括整数部分开头的 0
+    pub fn precision(&self) -> u32 {
+        let (_, int, frac) = self.parts();
+        (int.trim_start_matches('0').len() + frac.len()) as u32
+    }
+
+    pub fn is_negative(&self) -> bool {
+        let (negative, int, frac) = self.parts();
+        negative && (int.bytes().chain(frac.bytes()).any(|b| b != b'0'))
+    }
+
+    /// 小数部分是否全为 0
+    pub fn is_integer(&self) -> bool {
+        self.parts().2.bytes().all(|b| b == b'0')
+    }
+
+    /// 转换为最接近的 `f64`
+    pub fn to_f64(&self) -> f64 {
+        self.text.parse().unwrap_or(f64::NAN)
+    }
+
+    /// 整数部分，带符号，小数部分不为 0 时返回错误
+    fn integer(&self) -> Result<String, BoxDynError> {
+        if !self.is_integer() {
+            return Err(format!("NUMERIC value {} is not an integer", self.text).into());
+        }
+
+        let (_, int, _) = self.parts();
+        let int = match int.trim_start_matches('0') {
+            "" => "0",
+            int => int,
+        };
+        Ok(if self.is_negative() {
+            format!("-{int}")
+        } else {
+            int.to_owned()
+        })
+    }
+}
+
+/// 拆分为符号、整数部分和小数部分，格式为 `[+-]digits[.digits]`，整数部分和小数部分不能都为空
+fn split(s: &str) -> Option<(bool, &str, &str)> {
+    let (negative, s) = match s.strip_prefix('-') {
+        Some(s) => (true, s),
+        None => (false, s.strip_prefix('+').unwrap_or(s)),
+    };
+    let (int, frac) = s.split_once('.').unwrap_or((s, ""));
+
+    let digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    (digits(int) && digits(frac) && !(int.is_empty() && frac.is_empty()))
+        .then_some((negative, int, frac))
+}
+
+/// 按参数声明的 `NUMERIC(precision, scale)` 调整十进制数的文本
+///
+/// 小数位数超过标度时按 `rounding` 处理，多余的小数位全为 0 时直接去掉；
+/// 整数部分超出精度时返回错误。科学计数法（如 `BigDecimal` 输出的 `1e+30`、`1.5E-7`）
+/// 先展开为普通十进制数再检查。不是十进制数的文本原样返回，由服务器处理。
+pub(crate) fn fit_numeric(
+    text: &str,
+    precision: u32,
+    scale: u32,
+    rounding: XuguNumericRounding,
+) -> Result<Cow<'_, str>, BoxDynError> {
+    let Some((mantissa, exponent)) = text.trim().split_once(['e', 'E']) else {
+        return fit_plain(text, text, precision, scale, rounding);
+    };
+    let Some(plain) = expand_exponent(mantissa, exponent, precision, scale) else {
+        return Ok(Cow::Borrowed(text));
+    };
+
+    let fitted = fit_plain(&plain, text, precision, scale, rounding)?.into_owned();
+    Ok(Cow::Owned(fitted))
+}
+
+/// 将科学计数法的尾数和指数展开为普通十进制数，不是合法的十进制数时返回 `None`
+///
+/// 数量级远超出 `NUMERIC(precision, scale)` 时不展开全部的 0，而是返回同样超出精度、
+/// 或同样小于最小标度的值，舍入和检查的结果不变。
+fn expand_exponent(mantissa: &str, exponent: &str, precision: u32, scale: u32) -> Option<String> {
+    let (negative, int, frac) = split(mantissa)?;
+    if exponent.starts_with("+-") || exponent.starts_with("++") {
+        return None;
+    }
+    let exponent: i64 = exponent
+        .strip_prefix('+')
+        .unwrap_or(exponent)
+        .parse()
+        .ok()?;
+
+    let digits = format!("{int}{frac}");
+    let significant = digits.trim_start_matches('0');
+    let leading_zeros = digits.len() - significant.len();
+    let significant = significant.trim_end_matches('0');
+    if significant.is_empty() {
+        return Some(String::from("0"));
+    }
+
+    // 值为 0.{significant} × 10^point
+    let point = (int.len() as i64 - leading_zeros as i64).saturating_add(exponent);
+    let (significant, point) = if point > i64::from(precision) {
+        ("1", i64::from(precision) + 1)
+    } else if point < -i64::from(scale) - 1 {
+        ("1", -i64::from(scale) - 1)
+    } else {
+        (significant, point)
+    };
+
+    let mut plain = String::with_capacity(significant.len() + scale as usize + 3);
+    if negative {
+        plain.push('-');
+    }
+    if point <= 0 {
+        plain.push_str("0.");
+        plain.extend(std::iter::repeat('0').take(point.unsigned_abs() as usize));
+        plain.push_str(significant);
+    } else if point as usize >= significant.len() {
+        plain.push_str(significant);
+        plain.extend(std::iter::repeat('0').take(point as usize - significant.len()));
+    } else {
+        let (int, frac) = significant.split_at(point as usize);
+        plain.push_str(int);
+        plain.push('.');
+        plain.push_str(frac);
+    }
+
+    Some(plain)
+}
+
+/// 调整普通十进制数的文本，错误信息中使用原始文本 `original`
+fn fit_plain<'a>(
+    text: &'a str,
+    original: &str,
+    precision: u32,
+    scale: u32,
+    rounding: XuguNumericRounding,
+) -> Result<Cow<'a, str>, BoxDynError> {
+    let Some((negative, int, frac)) = split(text.trim()) else {
+        return Ok(Cow::Borrowed(text));
+    };
+    let scale = scale as usize;
+    let max_int_digits = (precision as usize).saturating_sub(scale);
+    let out_of_range =
+        || format!("value {original} is out of range for NUMERIC({precision},{scale})");
+
+    let int = int.trim_start_matches('0');
+    if frac.len() <= scale {
+        if int.len() > max_int_digits {
+            return Err(out_of_range().into());
+        }
+        return Ok(Cow::Borrowed(text));
+    }
+
+    let (kept, excess) = frac.split_at(scale);
+    let exact = excess.bytes().all(|b| b == b'0');
+    if !exact && rounding == XuguNumericRounding::Error {
+        return Err(format!(
+            "value {original} has more decimal places than the scale of NUMERIC({precision},{scale})"
+        )
+        .into());
+    }
+
+    let mut digits: Vec<u8> = int.bytes().chain(kept.bytes()).collect();
+    let round_up = rounding == XuguNumericRounding::HalfEven
+        && match excess.as_bytes() {
+            [first, rest @ ..] => {
+                *first > b'5'
+                    || (*first == b'5'
+                        && (rest.iter().any(|&b| b != b'0')
+                            || digits.last().is_some_and(|d| (d - b'0') % 2 == 1)))
+            }
+            [] => false,
+        };
+    if round_up {
+        let carry = digits.iter_mut().rev().all(|d| {
+            if *d == b'9' {
+                *d = b'0';
+                true
+            } else {
+                *d += 1;
+                false
+            }
+        });
+        if carry {
+            digits.insert(0, b'1');
+        }
+    }
+
+    let (int, frac) = digits.split_at(digits.len() - scale);
+    let int = std::str::from_utf8(int)?.trim_start_matches('0');
+    let frac = std::str::from_utf8(frac)?;
+    if int.len() > max_int_digits {
+        return Err(out_of_range().into());
+    }
+
+    let mut fitted = String::with_capacity(digits.len() + 2);
+    if negative && digits.iter().any(|&b| b != b'0') {
+        fitted.push('-');
+    }
+    fitted.push_str(if int.is_empty() { "0" } else { int });
+    if !frac.is_empty() {
+        fitted.push('.');
+        fitted.push_str(frac);
+    }
+
+    Ok(Cow::Owned(fitted))
+}
+
+impl FromStr for XgNumeric {
+    type Err = BoxDynError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        split(s).ok_or_else(|| format!("invalid NUMERIC value {s:?}"))?;
+
+        Ok(Self { text: s.to_owned() })
+    }
+}
+
+impl Display for XgNumeric {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+macro_rules! impl_integer_conversions {
+    ($($ty:ty),*) => {
+        $(
+            impl From<$ty> for XgNumeric {
+                fn from(value: $ty) -> Self {
+                    Self {
+                        text: value.to_string(),
+                    }
+                }
+            }
+
+            impl TryFrom<&XgNumeric> for $ty {
+                type Error = BoxDynError;
+
+                fn try_from(value: &XgNumeric) -> Result<Self, Self::Error> {
+                    value.integer()?.parse().map_err(|_| {
+                        format!(
+                            "NUMERIC value {} is out of range for {}",
+                            value.text,
+                            stringify!($ty)
+                        )
+                        .into()
+                    })
+                }
+            }
+        )*
+    };
+}
+
+impl_integer_conversions!(i64, u64, i128, u128, BigInt);
+
+/// 使用能精确还原该值的最短十进制表示，NaN 和无穷大返回错误
+impl TryFrom<f64> for XgNumeric {
+    type Error = BoxDynError;
+
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        if !value.is_finite() {
+            return Err(format!("{value} cannot be represented as NUMERIC").into());
+        }
+
+        Ok(Self {
+            text: value.to_string(),
+        })
+    }
+}
+
+impl From<&XgNumeric> for f64 {
+    fn from(value: &XgNumeric) -> Self {
+        value.to_f64()
+    }
+}
+
+impl Type<Xugu> for XgNumeric {
+    fn type_info() -> XuguTypeInfo {
+        XuguTypeInfo::binary(ColumnType::NUMERIC)
+    }
+
+    fn compatible(ty: &XuguTypeInfo) -> bool {
+        matches!(ty.r#type, ColumnType::NUMERIC | ColumnType::CHAR) || is_integer_type(ty)
+    }
+}
+
+impl Encode<'_, Xugu> for XgNumeric {
+    fn encode_by_ref(&self, args: &mut Vec<XuguArgumentValue>) -> Result<IsNull, BoxDynError> {
+        args.push(XuguArgumentValue::Numeric(Cow::Owned(self.text.clone())));
+
+        Ok(IsNull::No)
+    }
+
+    fn produces(&self) -> Option<XuguTypeInfo> {
+        Some(XuguTypeInfo::binary(ColumnType::CHAR))
+    }
+}
+
+impl Decode<'_, Xugu> for XgNumeric {
+    fn decode(value: XuguValueRef<'_>) -> Result<Self, BoxDynError> {
+        if is_integer_type(&value.type_info) {
+            return <i64 as Decode<Xugu>>::decode(value).map(Self::from);
+        }
+
+        value.as_str()?.parse()
+    }
+}
+
+impl_has_array_type!(XgNumeric => ARRAY_NUMERIC);
+
+fn is_integer_type(ty: &XuguTypeInfo) -> bool {
+    matches!(
+        ty.r#type,
+        ColumnType::TINYINT | ColumnType::SMALLINT | ColumnType::INTEGER | ColumnType::BIGINT
+    ) && !ty.flags.contains(ColumnFlags::IS_LOB)
+}
+
+/// 整数列按 `BIGINT` 解码，`NUMERIC` 列按文本解码
+pub(crate) fn decode_wide_integer<T>(value: XuguValueRef<'_>) -> Result<T, BoxDynError>
+where
+    T: for<'a> TryFrom<&'a XgNumeric, Error = BoxDynError>,
+{
+    let numeric = <XgNumeric as Decode<Xugu>>::decode(value)?;
+    T::try_from(&numeric)
+}
+
+impl Type<Xugu> for i128 {
+    fn type_info() -> XuguTypeInfo {
+        XuguTypeInfo::binary(ColumnType::NUMERIC)
+    }
+
+    fn compatible(ty: &XuguTypeInfo) -> bool {
+        <XgNumeric as Type<Xugu>>::compatible(ty)
+    }
+}
+
+impl Encode<'_, Xugu> for i128 {
+    fn encode_by_ref(&self, args: &mut Vec<XuguArgumentValue>) -> Result<IsNull, BoxDynError> {
+        <XgNumeric as Encode<Xugu>>::encode(XgNumeric::from(*self), args)
+    }
+
+    fn produces(&self) -> Option<XuguTypeInfo> {
+        Some(XuguTypeInfo::binary(ColumnType::CHAR))
+    }
+}
+
+impl Decode<'_, Xugu> for i128 {
+    fn decode(value: XuguValueRef<'_>) -> Result<Self, BoxDynError> {
+        decode_wide_integer(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numeric(s: &str) -> XgNumeric {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parse() {
+        for s in ["0", "-0", "+1", "007", "1.", ".5", "-.5", "1.2300", " 42 "] {
+            assert_eq!(numeric(s).as_str(), s.trim(), "{s}");
+        }
+        for s in [
+            "", ".", "-", "+-1", "1e5", "1.2.3", "0x10", "1 000", "NaN", "inf", "１",
+        ] {
+            assert!(s.parse::<XgNumeric>().is_err(), "{s}");
+        }
+    }
+
+    #[test]
+    fn precision_and_scale() {
+        let cases = [
+            ("0", 0, 0),
+            ("0.00", 2, 2),
+            ("007.50", 2, 3),
+            ("-12345678901234567890.500", 3, 23),
+            ("1.", 0, 1),
+            (".05", 2, 2),
+        ];
+        for (s, scale, precision) in cases {
+            let n = numeric(s);
+            assert_eq!(n.scale(), scale, "{s}");
+            assert_eq!(n.precision(), precision, "{s}");
+        }
+
+        assert!(numeric("-0.01").is_negative());
+        assert!(!numeric("-0.00").is_negative());
+        assert!(numeric("5.000").is_integer());
+        assert!(!numeric("5.001").is_integer());
+    }
+
+    #[test]
+    fn integer_conversions() {
+        assert_eq!(u64::try_from(&XgNumeric::from(u64::MAX)).unwrap(), u64::MAX);
+        assert_eq!(
+            i128::try_from(&XgNumeric::from(i128::MIN)).unwrap(),
+            i128::MIN
+        );
+        assert_eq!(
+            u128::try_from(&XgNumeric::from(u128::MAX)).unwrap(),
+            u128::MAX
+        );
+        let big = BigInt::from(u128::MAX) * BigInt::from(1000);
+        assert_eq!(
+            BigInt::try_from(&XgNumeric::from(big.clone())).unwrap(),
+            big
+        );
+
+        assert_eq!(i64::try_from(&numeric("-0042.000")).unwrap(), -42);
+        assert_eq!(i64::try_from(&numeric("-0.0")).unwrap(), 0);
+        assert_eq!(u64::try_from(&numeric("-0")).unwrap(), 0);
+        assert_eq!(i128::try_from(&numeric("+.0")).unwrap(), 0);
+
+        assert!(i64::try_from(&numeric("1.5")).is_err());
+        assert!(u64::try_from(&numeric("-1")).is_err());
+        assert!(u64::try_from(&numeric("18446744073709551616")).is_err());
+        assert!(i64::try_from(&numeric("9223372036854775808")).is_err());
+        assert_eq!(
+            i64::try_from(&numeric("-9223372036854775808")).unwrap(),
+            i64::MIN
+        );
+    }
+
+    #[test]
+    fn f64_conversions() {
+        for x in [0.1, -2.5, 1e-7, 1e300, f64::MAX, f64::MIN_POSITIVE] {
+            let n = XgNumeric::try_from(x).unwrap();
+            assert!(n.as_str().parse::<XgNumeric>().is_ok(), "{n}");
+            assert_eq!(f64::from(&n), x);
+        }
+        assert_eq!(XgNumeric::try_from(0.1).unwrap().as_str(), "0.1");
+        for x in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            assert!(XgNumeric::try_from(x).is_err());
+        }
+        assert_eq!(numeric("0.30000000000000004").to_f64(), 0.1 + 0.2);
+    }
+
+    fn decode(ty: ColumnType, bytes: &[u8]) -> Result<XgNumeric, BoxDynError> {
+        XgNumeric::decode(XuguValueRef {
+            value: Some(bytes),
+            row: None,
+            type_info: XuguTypeInfo {
+                r#type: ty,
+                flags: ColumnFlags::empty(),
+            },
+            time_zone: None,
+        })
+    }
+
+    #[test]
+    fn decode_by_column_type() {
+        assert_eq!(
+            decode(ColumnType::NUMERIC, b"123.4500").unwrap().as_str(),
+            "123.4500"
+        );
+        assert_eq!(
+            decode(ColumnType::BIGINT, &(-5_i64).to_be_bytes()).unwrap(),
+            XgNumeric::from(-5_i64)
+        );
+        assert!(decode(ColumnType::NUMERIC, b"abc").is_err());
+    }
+
+    #[test]
+    fn fit_numeric_table() {
+        use XuguNumericRounding::{Error, HalfEven, Truncate};
+
+        // (文本, 精度, 标度, 舍入方式, 结果)，`None` 表示返回错误
+        let cases: &[(&str, u32, u32, XuguNumericRounding, Option<&str>)] = &[
+            // 不需要调整时原样返回
+            ("12.5", 3, 1, Error, Some("12.5")),
+            ("12", 3, 1, Error, Some("12")),
+            ("+007.5", 3, 1, Error, Some("+007.5")),
+            ("-0012.50", 5, 2, Error, Some("-0012.50")),
+            ("123.4", 3, 1, Error, None),
+            ("+0123", 3, 1, Error, None),
+            // 进位后整数部分超出精度
+            ("99.95", 3, 1, HalfEven, None),
+            ("99.95", 3, 1, Truncate, Some("99.9")),
+            ("99.95", 3, 1, Error, None),
+            ("99.94", 3, 1, HalfEven, Some("99.9")),
+            ("-99.96", 3, 1, HalfEven, None),
+            ("99.95", 4, 1, HalfEven, Some("100.0")),
+            // 五成双
+            ("0.125", 10, 2, HalfEven, Some("0.12")),
+            ("0.135", 10, 2, HalfEven, Some("0.14")),
+            ("0.1251", 10, 2, HalfEven, Some("0.13")),
+            ("-0.125", 10, 2, HalfEven, Some("-0.12")),
+            ("-0.135", 10, 2, HalfEven, Some("-0.14")),
+            ("2.5", 10, 0, HalfEven, Some("2")),
+            ("3.5", 10, 0, HalfEven, Some("4")),
+            ("0.125", 10, 2, Truncate, Some("0.12")),
+            ("0.135", 10, 2, Truncate, Some("0.13")),
+            ("0.125", 10, 2, Error, None),
+            // 多余的小数位全为 0 时总是直接去掉
+            ("0.1200", 10, 2, Error, Some("0.12")),
+            ("5.000", 1, 0, Error, Some("5")),
+            // 结果为 0 时不保留负号
+            ("-0.001", 10, 2, Truncate, Some("0.00")),
+            ("-0.001", 10, 2, HalfEven, Some("0.00")),
+            ("-0.005", 10, 2, HalfEven, Some("0.00")),
+            ("-0.006", 10, 2, HalfEven, Some("-0.01")),
+            ("-0.001", 10, 0, Truncate, Some("0")),
+            // 开头的 `+` 和 0
+            ("+1.25", 10, 1, HalfEven, Some("1.2")),
+            ("+0001.35", 10, 1, HalfEven, Some("1.4")),
+            ("-000.999", 10, 2, HalfEven, Some("-1.00")),
+            ("00099.99", 3, 1, HalfEven, None),
+            (".55", 10, 1, HalfEven, Some("0.6")),
+            // 精度等于标度：整数部分只能为 0
+            ("0.99", 2, 2, Error, Some("0.99")),
+            ("-.5", 2, 2, Error, Some("-.5")),
+            ("1.0", 2, 2, Error, None),
+            ("0.995", 2, 2, HalfEven, None),
+            ("0.995", 2, 2, Truncate, Some("0.99")),
+            ("-0.994", 2, 2, HalfEven, Some("-0.99")),
+            ("0", 2, 2, Error, Some("0")),
+            // 不是普通十进制数的文本交给服务器处理
+            ("abc", 3, 1, Error, Some("abc")),
+            ("1e", 3, 1, Error, Some("1e")),
+            ("e5", 3, 1, Error, Some("e5")),
+            ("1e+-5", 3, 1, Error, Some("1e+-5")),
+            (
+                "1e99999999999999999999",
+                3,
+                1,
+                Error,
+                Some("1e99999999999999999999"),
+            ),
+            // 科学计数法先展开再检查
+            ("1e5", 3, 1, Error, None),
+            ("1e5", 6, 0, Error, Some("100000")),
+            ("1.25e+1", 10, 1, Error, Some("12.5")),
+            ("-1.25E1", 10, 0, HalfEven, Some("-12")),
+            ("0.0125e3", 10, 1, HalfEven, Some("12.5")),
+            ("125e-1", 10, 1, Error, Some("12.5")),
+            ("1e+30", 10, 2, HalfEven, None),
+            ("1e+99999999999", 10, 2, Truncate, None),
+            ("1E-20", 10, 2, HalfEven, Some("0.00")),
+            ("1E-20", 10, 2, Error, None),
+            ("-1e-99999999999", 10, 2, Truncate, Some("0.00")),
+            ("5e-3", 10, 2, HalfEven, Some("0.00")),
+            ("5.1e-3", 10, 2, HalfEven, Some("0.01")),
+            ("1.23456E-7", 20, 12, Error, Some("0.000000123456")),
+            ("1.23456E-7", 20, 10, HalfEven, Some("0.0000001235")),
+            ("-0e10", 3, 1, Error, Some("0")),
+        ];
+
+        for &(text, precision, scale, rounding, expected) in cases {
+            let result = fit_numeric(text, precision, scale, rounding);
+            assert_eq!(
+                result.as_deref().ok(),
+                expected,
+                "{text} NUMERIC({precision},{scale}) {rounding:?}: {result:?}"
+            );
+        }
+
+        let err = fit_numeric("99.95", 3, 1, HalfEven).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "value 99.95 is out of range for NUMERIC(3,1)"
+        );
+
+        // 十进制数类型按精度和标度调整，字符串原样发送
+        assert_eq!(encoded(numeric("1.50")), "1.50");
+        assert_eq!(encoded(-7_i128), "-7");
+        let mut args = Vec::new();
+        assert!(matches!(
+            Encode::<Xugu>::encode("1.50", &mut args),
+            Ok(IsNull::No)
+        ));
+        assert!(matches!(args[..], [XuguArgumentValue::Str(_)]));
+    }
+
+    /// 十进制数类型编码后的文本
+    fn encoded<'q>(value: impl Encode<'q, Xugu>) -> String {
+        let mut args = Vec::new();
+        assert!(matches!(value.encode(&mut args), Ok(IsNull::No)));
+        match args.pop() {
+            Some(XuguArgumentValue::Numeric(text)) => text.into_owned(),
+            other => panic!("unexpected argument {other:?}"),
+        }
+    }
+
+    #[cfg(feature = "bigdecimal")]
+    #[test]
+    fn fit_bigdecimal() {
+        use bigdecimal::BigDecimal;
+        use XuguNumericRounding::{Error, HalfEven, Truncate};
+
+        // `BigDecimal` 的文本可能使用科学计数法
+        let cases: &[(&str, u32, u32, XuguNumericRounding, Option<&str>)] = &[
+            ("1e30", 10, 2, HalfEven, None),
+            (
+                "1e30",
+                31,
+                0,
+                Error,
+                Some("1000000000000000000000000000000"),
+            ),
+            ("1E-20", 10, 2, HalfEven, Some("0.00")),
+            ("1E-20", 10, 2, Error, None),
+            ("1.23456E-7", 20, 12, Error, Some("0.000000123456")),
+            ("-1.5E-7", 10, 6, HalfEven, Some("0.000000")),
+            ("-1.5E-6", 10, 6, HalfEven, Some("-0.000002")),
+            ("123.455", 5, 2, HalfEven, Some("123.46")),
+            ("-0.000001", 5, 2, Truncate, Some("0.00")),
+            (
+                "12345678901234567890.5",
+                20,
+                0,
+                HalfEven,
+                Some("12345678901234567890"),
+            ),
+        ];
+        for &(value, precision, scale, rounding, expected) in cases {
+            let text = encoded(value.parse::<BigDecimal>().unwrap());
+            let result = fit_numeric(&text, precision, scale, rounding);
+            assert_eq!(
+                result.as_deref().ok(),
+                expected,
+                "{value} ({text}) NUMERIC({precision},{scale}) {rounding:?}: {result:?}"
+            );
+        }
+    }
+
+    #[cfg(feature = "rust_decimal")]
+    #[test]
+    fn fit_decimal() {
+        use rust_decimal::Decimal;
+        use XuguNumericRounding::{Error, HalfEven, Truncate};
+
+        let cases: &[(Decimal, u32, u32, XuguNumericRounding, Option<&str>)] = &[
+            (
+                Decimal::from_scientific("1.23456e-7").unwrap(),
+                20,
+                12,
+                Error,
+                Some("0.000000123456"),
+            ),
+            (
+                Decimal::from_scientific("1.23456e-7").unwrap(),
+                20,
+                6,
+                Error,
+                None,
+            ),
+            (
+                Decimal::from_scientific("1e20").unwrap(),
+                21,
+                0,
+                Error,
+                Some("100000000000000000000"),
+            ),
+            (
+                Decimal::from_scientific("1e20").unwrap(),
+                21,
+                1,
+                Error,
+                None,
+            ),
+            (
+                Decimal::MAX,
+                29,
+                0,
+                Error,
+                Some("79228162514264337593543950335"),
+            ),
+            (Decimal::MAX, 28, 0, HalfEven, None),
+            (Decimal::new(25, 1), 10, 0, HalfEven, Some("2")),
+            (Decimal::new(-35, 1), 10, 0, HalfEven, Some("-4")),
+            (Decimal::new(-35, 1), 10, 0, Truncate, Some("-3")),
+            (Decimal::new(12000, 4), 3, 1, Error, Some("1.2")),
+        ];
+        for &(value, precision, scale, rounding, expected) in cases {
+            let text = encoded(value);
+            let result = fit_numeric(&text, precision, scale, rounding);
+            assert_eq!(
+                result.as_deref().ok(),
+                expected,
+                "{text} NUMERIC({precision},{scale}) {rounding:?}: {result:?}"
+            );
+        }
+    }
+}
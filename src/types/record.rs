@@ -0,0 +1,461 @@
+use crate::types::array::{encode_array, parse_array, parse_elements, push_element};
+use crate::types::XuguHasArrayType;
+use crate::XuguValueRef;
+use sqlx_core::error::BoxDynError;
+use std::vec::IntoIter;
+
+/// 组合类型（`OBJECT`、`RECORD`）的编码器
+///
+/// 按字段顺序生成字面量 `(1,"a b",NULL,"{1,2}")`，字段的文本表示由 [`XuguHasArrayType`] 提供，
+/// 集合字段按数组字面量编码。
+///
+/// 一般通过 [`xugu_record!`][crate::xugu_record] 为结构体生成组合类型的实现；
+/// 需要自定义字段的编解码时，可以参照下面的示例手动实现：
+///
+/// ```rust
+/// # use sqlx_core::decode::Decode;
+/// # use sqlx_core::encode::{Encode, IsNull};
+/// # use sqlx_core::error::BoxDynError;
+/// # use sqlx_core::types::Type;
+/// # use sqlx_xugu::types::{XuguHasArrayType, XuguRecordDecoder, XuguRecordEncoder};
+/// # use sqlx_xugu::{Xugu, XuguTypeInfo, XuguValueRef};
+/// # use sqlx_core::database::Database;
+/// // CREATE TYPE address AS OBJECT(city VARCHAR, zip INTEGER, tags VARRAY(8) OF VARCHAR)
+/// #[derive(Debug, PartialEq)]
+/// struct Address {
+///     city: String,
+///     zip: Option<i32>,
+///     tags: Vec<String>,
+/// }
+///
+/// impl XuguHasArrayType for Address {
+///     fn array_type_info() -> XuguTypeInfo {
+///         XuguTypeInfo::object_array()
+///     }
+///
+///     fn encode_element(&self) -> Result<Option<String>, BoxDynError> {
+///         let mut encoder = XuguRecordEncoder::new();
+///         encoder.encode(&self.city)?.encode(&self.zip)?.encode_array(&self.tags)?;
+///         Ok(Some(encoder.finish()))
+///     }
+///
+///     fn decode_element(text: Option<&str>) -> Result<Self, BoxDynError> {
+///         let mut decoder = XuguRecordDecoder::from_text(text.ok_or("unexpected NULL")?)?;
+///         Ok(Address {
+///             city: decoder.try_decode()?,
+///             zip: decoder.try_decode()?,
+///             tags: decoder.try_decode_array()?,
+///         })
+///     }
+/// }
+///
+/// impl Type<Xugu> for Address {
+///     fn type_info() -> XuguTypeInfo {
+///         XuguTypeInfo::object()
+///     }
+///
+///     fn compatible(ty: &XuguTypeInfo) -> bool {
+///         ty.is_composite()
+///     }
+/// }
+///
+/// impl Encode<'_, Xugu> for Address {
+///     fn encode_by_ref(
+///         &self,
+///         args: &mut <Xugu as Database>::ArgumentBuffer<'_>,
+///     ) -> Result<IsNull, BoxDynError> {
+///         let literal = self.encode_element()?.unwrap_or_default();
+///         <String as Encode<Xugu>>::encode(literal, args)
+///     }
+///
+///     // 以文本字面量发送，由服务器转换为组合类型
+///     fn produces(&self) -> Option<XuguTypeInfo> {
+///         Some(<String as Type<Xugu>>::type_info())
+///     }
+/// }
+///
+/// impl Decode<'_, Xugu> for Address {
+///     fn decode(value: XuguValueRef<'_>) -> Result<Self, BoxDynError> {
+///         let mut decoder = XuguRecordDecoder::new(value)?;
+///         Ok(Address {
+///             city: decoder.try_decode()?,
+///             zip: decoder.try_decode()?,
+///             tags: decoder.try_decode_array()?,
+///         })
+///     }
+/// }
+///
+/// let address = Address {
+///     city: "Wuhan".into(),
+///     zip: None,
+///     tags: vec!["home".into(), "a b".into()],
+/// };
+/// let literal = address.encode_element().unwrap().unwrap();
+/// assert_eq!(literal, r#"(Wuhan,NULL,"{home,\"a b\"}")"#);
+/// assert_eq!(Address::decode_element(Some(&literal)).unwrap(), address);
+/// ```
+///
+/// ## 字面量格式
+///
+/// * 字面量格式 `(a,"b",NULL)` 参照 PostgreSQL 的组合类型文本格式，
+///   尚未对照虚谷服务器实际返回的格式验证；
+/// * 编码时包含特殊字符的字段、集合字段以及数组中的组合类型元素都加引号，如 `{"(1,a)","(2,b)"}`；
+///   解码时同时接受未加引号的形式，如 `{(1,a),(2,b)}`、`(1,{2,3})`；
+/// * 嵌套集合字段（如 `Vec<Vec<T>>`）的子数组同样加引号编码，解码时接受 `{{1,2},{3}}`；
+/// * sqlx 的 `#[derive(sqlx::Type)]` 不会为虚谷生成组合类型的实现，请使用
+///   [`xugu_record!`][crate::xugu_record]。
+#[derive(Debug)]
+pub struct XuguRecordEncoder {
+    buf: String,
+    num_fields: usize,
+}
+
+impl XuguRecordEncoder {
+    pub fn new() -> Self {
+        Self {
+            buf: String::from("("),
+            num_fields: 0,
+        }
+    }
+
+    fn push(&mut self, text: Option<&str>) {
+        if self.num_fields > 0 {
+            self.buf.push(',');
+        }
+        match text {
+            Some(text) => push_element(&mut self.buf, text),
+            None => self.buf.push_str("NULL"),
+        }
+        self.num_fields += 1;
+    }
+
+    /// 编码下一个字段
+    pub fn encode<T: XuguHasArrayType>(&mut self, value: &T) -> Result<&mut Self, BoxDynError> {
+        let text = value.encode_element()?;
+        self.push(text.as_deref());
+
+        Ok(self)
+    }
+
+    /// 编码下一个集合字段（`VARRAY`、`TABLE` 或数组）
+    pub fn encode_array<T: XuguHasArrayType>(
+        &mut self,
+        values: &[T],
+    ) -> Result<&mut Self, BoxDynError> {
+        let text = encode_array(values)?;
+        self.push(Some(&text));
+
+        Ok(self)
+    }
+
+    /// 结束编码，返回组合类型的字面量
+    pub fn finish(mut self) -> String {
+        self.buf.push(')');
+        self.buf
+    }
+}
+
+impl Default for XuguRecordEncoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 组合类型（`OBJECT`、`RECORD`）的解码器，按字段顺序解码
+///
+/// 示例见 [`XuguRecordEncoder`]。
+#[derive(Debug)]
+pub struct XuguRecordDecoder {
+    fields: IntoIter<Option<String>>,
+    index: usize,
+}
+
+impl XuguRecordDecoder {
+    pub fn new(value: XuguValueRef<'_>) -> Result<Self, BoxDynError> {
+        Self::from_text(value.as_str()?)
+    }
+
+    /// 解析组合类型的字面量 `(...)`，用于实现 [`XuguHasArrayType::decode_element`]
+    pub fn from_text(text: &str) -> Result<Self, BoxDynError> {
+        let text = text.trim();
+        let inner = text
+            .strip_prefix('(')
+            .and_then(|s| s.strip_suffix(')'))
+            .ok_or_else(|| format!("invalid record literal {text:?}"))?;
+
+        Ok(Self {
+            fields: parse_elements(inner, text, true)?.into_iter(),
+            index: 0,
+        })
+    }
+
+    fn next(&mut self) -> Result<Option<String>, BoxDynError> {
+        let field = self
+            .fields
+            .next()
+            .ok_or_else(|| format!("record has no field at index {}", self.index))?;
+        self.index += 1;
+
+        Ok(field)
+    }
+
+    /// 解码下一个字段
+    pub fn try_decode<T: XuguHasArrayType>(&mut self) -> Result<T, BoxDynError> {
+        let index = self.index;
+        let field = self.next()?;
+        T::decode_element(field.as_deref())
+            .map_err(|e| format!("error decoding record field {index}: {e}").into())
+    }
+
+    /// 解码下一个集合字段，NULL 解码为空集合
+    pub fn try_decode_array<T: XuguHasArrayType>(&mut self) -> Result<Vec<T>, BoxDynError> {
+        let index = self.index;
+        let Some(field) = self.next()? else {
+            return Ok(Vec::new());
+        };
+
+        parse_array(&field)?
+            .iter()
+            .map(|element| T::decode_element(element.as_deref()))
+            .collect::<Result<_, _>>()
+            .map_err(|e| format!("error decoding record field {index}: {e}").into())
+    }
+}
+
+/// 为具名字段的结构体实现组合类型（`OBJECT`、`RECORD`）的编解码
+///
+/// 按列出的字段顺序生成 [`XuguHasArrayType`]、`Type`、`Encode` 和 `Decode` 的实现，
+/// 需要列出结构体的全部字段，顺序与 `CREATE TYPE` 中的属性顺序一致。
+/// 字段类型需要实现 [`XuguHasArrayType`]：集合字段使用 `Vec<T>`（嵌套集合为 `Vec<Vec<T>>`），
+/// 可以为 NULL 的字段使用 `Option<T>`，包括可以为 NULL 的集合 `Option<Vec<T>>`。
+/// 生成的实现与 [`XuguRecordEncoder`] 示例中的手动实现相同。
+///
+/// ```rust
+/// use sqlx_xugu::types::XuguHasArrayType;
+///
+/// // CREATE TYPE address AS OBJECT(city VARCHAR, zip INTEGER, tags VARRAY(8) OF VARCHAR)
+/// #[derive(Debug, PartialEq)]
+/// struct Address {
+///     city: String,
+///     zip: Option<i32>,
+///     tags: Vec<String>,
+/// }
+///
+/// sqlx_xugu::xugu_record!(Address { city, zip, tags });
+///
+/// let address = Address {
+///     city: "Wuhan".into(),
+///     zip: None,
+///     tags: vec!["home".into()],
+/// };
+/// let literal = address.encode_element().unwrap().unwrap();
+/// assert_eq!(literal, r#"(Wuhan,NULL,"{home}")"#);
+/// assert_eq!(Address::decode_element(Some("(Wuhan, NULL, {home})")).unwrap(), address);
+/// ```
+#[macro_export]
+macro_rules! xugu_record {
+    ($name:ident { $($field:ident),+ $(,)? }) => {
+        impl $crate::types::XuguHasArrayType for $name {
+            fn array_type_info() -> $crate::XuguTypeInfo {
+                $crate::XuguTypeInfo::object_array()
+            }
+
+            fn encode_element(
+                &self,
+            ) -> ::std::result::Result<
+                ::std::option::Option<::std::string::String>,
+                $crate::__sqlx_core::error::BoxDynError,
+            > {
+                let mut encoder = $crate::types::XuguRecordEncoder::new();
+                $(encoder.encode(&self.$field)?;)+
+                ::std::result::Result::Ok(::std::option::Option::Some(encoder.finish()))
+            }
+
+            fn decode_element(
+                text: ::std::option::Option<&str>,
+            ) -> ::std::result::Result<Self, $crate::__sqlx_core::error::BoxDynError> {
+                let text = text.ok_or(concat!("unexpected NULL for ", stringify!($name)))?;
+                let mut decoder = $crate::types::XuguRecordDecoder::from_text(text)?;
+                ::std::result::Result::Ok(Self {
+                    $($field: decoder.try_decode()?,)+
+                })
+            }
+        }
+
+        impl $crate::__sqlx_core::types::Type<$crate::Xugu> for $name {
+            fn type_info() -> $crate::XuguTypeInfo {
+                $crate::XuguTypeInfo::object()
+            }
+
+            fn compatible(ty: &$crate::XuguTypeInfo) -> bool {
+                ty.is_composite()
+            }
+        }
+
+        impl $crate::__sqlx_core::encode::Encode<'_, $crate::Xugu> for $name {
+            fn encode_by_ref(
+                &self,
+                args: &mut <$crate::Xugu as $crate::__sqlx_core::database::Database>::ArgumentBuffer<'_>,
+            ) -> ::std::result::Result<
+                $crate::__sqlx_core::encode::IsNull,
+                $crate::__sqlx_core::error::BoxDynError,
+            > {
+                let literal = $crate::types::XuguHasArrayType::encode_element(self)?;
+                $crate::__sqlx_core::encode::Encode::<$crate::Xugu>::encode(
+                    literal.unwrap_or_default(),
+                    args,
+                )
+            }
+
+            fn produces(&self) -> ::std::option::Option<$crate::XuguTypeInfo> {
+                ::std::option::Option::Some(
+                    <::std::string::String as $crate::__sqlx_core::types::Type<$crate::Xugu>>::type_info(),
+                )
+            }
+        }
+
+        impl $crate::__sqlx_core::decode::Decode<'_, $crate::Xugu> for $name {
+            fn decode(
+                value: $crate::XuguValueRef<'_>,
+            ) -> ::std::result::Result<Self, $crate::__sqlx_core::error::BoxDynError> {
+                let mut decoder = $crate::types::XuguRecordDecoder::new(value)?;
+                ::std::result::Result::Ok(Self {
+                    $($field: decoder.try_decode()?,)+
+                })
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_fields() {
+        let mut decoder =
+            XuguRecordDecoder::from_text(r#" (1, "a,b" ,NULL,f(x,y),{1,2},"{3,\"4 5\"}") "#)
+                .unwrap();
+        assert_eq!(decoder.try_decode::<i32>().unwrap(), 1);
+        assert_eq!(decoder.try_decode::<String>().unwrap(), "a,b");
+        assert_eq!(decoder.try_decode::<Option<i64>>().unwrap(), None);
+        assert_eq!(decoder.try_decode::<String>().unwrap(), "f(x,y)");
+        assert_eq!(decoder.try_decode_array::<i32>().unwrap(), [1, 2]);
+        assert_eq!(decoder.try_decode_array::<String>().unwrap(), ["3", "4 5"]);
+        assert!(decoder.try_decode::<i32>().is_err());
+    }
+
+    #[test]
+    fn encode_fields() {
+        let mut encoder = XuguRecordEncoder::new();
+        encoder
+            .encode(&1_i32)
+            .unwrap()
+            .encode(&"f(x)".to_owned())
+            .unwrap()
+            .encode(&None::<i32>)
+            .unwrap()
+            .encode_array(&[Some(1_i32), None])
+            .unwrap();
+        let literal = encoder.finish();
+        assert_eq!(literal, r#"(1,"f(x)",NULL,"{1,NULL}")"#);
+
+        let mut decoder = XuguRecordDecoder::from_text(&literal).unwrap();
+        assert_eq!(decoder.try_decode::<i32>().unwrap(), 1);
+        assert_eq!(decoder.try_decode::<String>().unwrap(), "f(x)");
+        assert_eq!(decoder.try_decode::<Option<i32>>().unwrap(), None);
+        assert_eq!(
+            decoder.try_decode_array::<Option<i32>>().unwrap(),
+            [Some(1), None]
+        );
+    }
+
+    #[test]
+    fn reject_invalid_literals() {
+        for text in ["1,2", "(1,2", "(f(x,2)", "(\"a)", "({1,2)"] {
+            assert!(XuguRecordDecoder::from_text(text).is_err(), "{text}");
+        }
+        let mut decoder = XuguRecordDecoder::from_text("({{1},{2}})").unwrap();
+        assert!(decoder.try_decode_array::<i32>().is_err());
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Item {
+        id: i32,
+        name: Option<String>,
+        grid: Vec<Vec<i32>>,
+    }
+
+    crate::xugu_record!(Item { id, name, grid });
+
+    #[test]
+    fn record_macro() {
+        use crate::protocol::text::{ColumnFlags, ColumnType};
+        use crate::{Xugu, XuguTypeInfo};
+        use sqlx_core::decode::Decode;
+        use sqlx_core::encode::Encode;
+        use sqlx_core::types::Type;
+
+        let item = Item {
+            id: 1,
+            name: Some("a,b".into()),
+            grid: vec![vec![1, 2], vec![3]],
+        };
+        let literal = item.encode_element().unwrap().unwrap();
+        assert_eq!(literal, r#"(1,"a,b","{\"{1,2}\",\"{3}\"}")"#);
+        assert_eq!(Item::decode_element(Some(&literal)).unwrap(), item);
+        assert_eq!(
+            Item::decode_element(Some(r#"(1,"a,b",{{1,2},{3}})"#)).unwrap(),
+            item
+        );
+        assert!(Item::decode_element(Some("(1,NULL)")).is_err());
+        assert!(Item::decode_element(None).is_err());
+
+        assert!(<Item as Type<Xugu>>::compatible(&XuguTypeInfo::record()));
+        assert_eq!(
+            Encode::<Xugu>::produces(&item).map(|ty| ty.r#type),
+            Some(ColumnType::CHAR)
+        );
+        let decoded = Item::decode(XuguValueRef {
+            value: Some(literal.as_bytes()),
+            row: None,
+            type_info: XuguTypeInfo {
+                r#type: ColumnType::OBJECT,
+                flags: ColumnFlags::empty(),
+            },
+            time_zone: None,
+        })
+        .unwrap();
+        assert_eq!(decoded, item);
+    }
+
+    #[test]
+    fn array_of_unquoted_records() {
+        let items =
+            Vec::<Item>::decode_element(Some(r#"{(1,a,{}),(2,NULL,{{4}}),"(3,\"c,d\",{})"}"#))
+                .unwrap();
+        assert_eq!(
+            items,
+            [
+                Item {
+                    id: 1,
+                    name: Some("a".into()),
+                    grid: vec![],
+                },
+                Item {
+                    id: 2,
+                    name: None,
+                    grid: vec![vec![4]],
+                },
+                Item {
+                    id: 3,
+                    name: Some("c,d".into()),
+                    grid: vec![],
+                },
+            ]
+        );
+
+        let text = encode_array(&items).unwrap();
+        assert_eq!(Vec::<Item>::decode_element(Some(&text)).unwrap(), items);
+    }
+}
@@ -1,5 +1,6 @@
 use crate::arguments::XuguArgumentValue;
 use crate::protocol::text::ColumnType;
+use crate::types::array::impl_has_array_type;
 use crate::{Xugu, XuguTypeInfo, XuguValueRef};
 
 use rust_decimal::Decimal;
@@ -21,7 +22,7 @@ impl Type<Xugu> for Decimal {
 
 impl<'q> Encode<'q, Xugu> for Decimal {
     fn encode_by_ref(&self, args: &mut Vec<XuguArgumentValue<'q>>) -> Result<IsNull, BoxDynError> {
-        args.push(XuguArgumentValue::Str(Cow::Owned(self.to_string())));
+        args.push(XuguArgumentValue::Numeric(Cow::Owned(self.to_string())));
 
         Ok(IsNull::No)
     }
@@ -36,3 +37,5 @@ impl Decode<'_, Xugu> for Decimal {
         Ok(value.as_str()?.parse()?)
     }
 }
+
+impl_has_array_type!(Decimal => ARRAY_NUMERIC);
@@ -0,0 +1,218 @@
+use super::{XgCoord, XgGeometry, XgShape};
+use crate::arguments::XuguArgumentValue;
+use crate::protocol::text::ColumnType;
+use crate::{Xugu, XuguTypeInfo, XuguValueRef};
+use geo_types::{
+    Coord, Geometry, GeometryCollection, LineString, MultiLineString, MultiPoint, MultiPolygon,
+    Point, Polygon,
+};
+use sqlx_core::decode::Decode;
+use sqlx_core::encode::{Encode, IsNull};
+use sqlx_core::error::BoxDynError;
+use sqlx_core::types::Type;
+
+impl From<Coord<f64>> for XgCoord {
+    fn from(c: Coord<f64>) -> Self {
+        Self { x: c.x, y: c.y }
+    }
+}
+
+impl From<XgCoord> for Coord<f64> {
+    fn from(c: XgCoord) -> Self {
+        Coord { x: c.x, y: c.y }
+    }
+}
+
+fn coords(line: &LineString<f64>) -> Vec<XgCoord> {
+    line.0.iter().copied().map(XgCoord::from).collect()
+}
+
+fn rings(polygon: &Polygon<f64>) -> Vec<Vec<XgCoord>> {
+    // 外环和内环都没有坐标时为空多边形；只有外环为空时保留空的外环，不丢弃内环
+    if polygon.exterior().0.is_empty() && polygon.interiors().is_empty() {
+        return Vec::new();
+    }
+
+    std::iter::once(polygon.exterior())
+        .chain(polygon.interiors())
+        .map(coords)
+        .collect()
+}
+
+fn line_string(coords: Vec<XgCoord>) -> LineString<f64> {
+    coords.into_iter().map(Coord::from).collect()
+}
+
+fn polygon(rings: Vec<Vec<XgCoord>>) -> Polygon<f64> {
+    let mut rings = rings.into_iter().map(line_string);
+    let exterior = rings.next().unwrap_or_else(|| LineString(Vec::new()));
+    Polygon::new(exterior, rings.collect())
+}
+
+/// `Line`、`Rect` 和 `Triangle` 分别转换为折线和多边形
+impl From<&Geometry<f64>> for XgShape {
+    fn from(geometry: &Geometry<f64>) -> Self {
+        match geometry {
+            Geometry::Point(p) => XgShape::Point(p.0.into()),
+            Geometry::Line(line) => XgShape::LineString(vec![line.start.into(), line.end.into()]),
+            Geometry::LineString(line) => XgShape::LineString(coords(line)),
+            Geometry::Polygon(p) => XgShape::Polygon(rings(p)),
+            Geometry::MultiPoint(points) => {
+                XgShape::MultiPoint(points.iter().map(|p| p.0.into()).collect())
+            }
+            Geometry::MultiLineString(lines) => {
+                XgShape::MultiLineString(lines.iter().map(coords).collect())
+            }
+            Geometry::MultiPolygon(polygons) => {
+                XgShape::MultiPolygon(polygons.iter().map(rings).collect())
+            }
+            Geometry::GeometryCollection(geometries) => {
+                XgShape::GeometryCollection(geometries.iter().map(XgShape::from).collect())
+            }
+            Geometry::Rect(rect) => XgShape::Polygon(rings(&rect.to_polygon())),
+            Geometry::Triangle(triangle) => XgShape::Polygon(rings(&triangle.to_polygon())),
+        }
+    }
+}
+
+impl From<Geometry<f64>> for XgShape {
+    fn from(geometry: Geometry<f64>) -> Self {
+        Self::from(&geometry)
+    }
+}
+
+impl From<XgShape> for Geometry<f64> {
+    fn from(shape: XgShape) -> Self {
+        match shape {
+            XgShape::Point(c) => Geometry::Point(Point(c.into())),
+            XgShape::LineString(line) => Geometry::LineString(line_string(line)),
+            XgShape::Polygon(rings) => Geometry::Polygon(polygon(rings)),
+            XgShape::MultiPoint(points) => Geometry::MultiPoint(MultiPoint(
+                points.into_iter().map(|c| Point(c.into())).collect(),
+            )),
+            XgShape::MultiLineString(lines) => Geometry::MultiLineString(MultiLineString(
+                lines.into_iter().map(line_string).collect(),
+            )),
+            XgShape::MultiPolygon(polygons) => {
+                Geometry::MultiPolygon(MultiPolygon(polygons.into_iter().map(polygon).collect()))
+            }
+            XgShape::GeometryCollection(shapes) => Geometry::GeometryCollection(
+                GeometryCollection(shapes.into_iter().map(Geometry::from).collect()),
+            ),
+        }
+    }
+}
+
+impl From<Geometry<f64>> for XgGeometry {
+    fn from(geometry: Geometry<f64>) -> Self {
+        Self::new(geometry.into())
+    }
+}
+
+/// 忽略 SRID
+impl From<XgGeometry> for Geometry<f64> {
+    fn from(geometry: XgGeometry) -> Self {
+        geometry.shape.into()
+    }
+}
+
+impl Type<Xugu> for Geometry<f64> {
+    fn type_info() -> XuguTypeInfo {
+        <XgGeometry as Type<Xugu>>::type_info()
+    }
+
+    fn compatible(ty: &XuguTypeInfo) -> bool {
+        <XgGeometry as Type<Xugu>>::compatible(ty)
+    }
+}
+
+/// 按不带 SRID 的 WKT 编码，需要 SRID 时使用 [`XgGeometry`]
+impl Encode<'_, Xugu> for Geometry<f64> {
+    fn encode_by_ref(&self, args: &mut Vec<XuguArgumentValue>) -> Result<IsNull, BoxDynError> {
+        <XgGeometry as Encode<Xugu>>::encode(XgGeometry::new(self.into()), args)
+    }
+
+    fn produces(&self) -> Option<XuguTypeInfo> {
+        Some(XuguTypeInfo::binary(ColumnType::CHAR))
+    }
+}
+
+impl<'r> Decode<'r, Xugu> for Geometry<f64> {
+    fn decode(value: XuguValueRef<'r>) -> Result<Self, BoxDynError> {
+        <XgGeometry as Decode<Xugu>>::decode(value).map(Geometry::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geo_types::{coord, line_string, point, polygon};
+
+    fn round_trip(geometry: Geometry<f64>) {
+        let shape = XgShape::from(&geometry);
+        assert_eq!(Geometry::from(shape.clone()), geometry);
+
+        let xg = XgGeometry::new(shape);
+        let wkt: XgGeometry = xg.to_string().parse().unwrap();
+        assert_eq!(Geometry::from(wkt), geometry);
+        let wkb = XgGeometry::from_wkb(&xg.to_wkb()).unwrap();
+        assert_eq!(Geometry::from(wkb), geometry);
+    }
+
+    #[test]
+    fn geometries_round_trip() {
+        let square = polygon![
+            exterior: [(x: 0., y: 0.), (x: 4., y: 0.), (x: 4., y: 4.), (x: 0., y: 0.)],
+            interiors: [[(x: 1., y: 1.), (x: 2., y: 1.), (x: 2., y: 2.), (x: 1., y: 1.)]],
+        ];
+        round_trip(Geometry::Point(point!(x: 1.5, y: -2.0)));
+        round_trip(Geometry::LineString(
+            line_string![(x: 0., y: 0.), (x: 1., y: 1.)],
+        ));
+        round_trip(Geometry::Polygon(square.clone()));
+        round_trip(Geometry::Polygon(Polygon::new(
+            LineString(Vec::new()),
+            Vec::new(),
+        )));
+        round_trip(Geometry::MultiPolygon(MultiPolygon(vec![square.clone()])));
+        round_trip(Geometry::GeometryCollection(GeometryCollection(vec![
+            Geometry::Point(point!(x: 1., y: 2.)),
+            Geometry::Polygon(square),
+        ])));
+    }
+
+    #[test]
+    fn polygon_with_empty_exterior_keeps_interiors() {
+        let hole = line_string![(x: 1., y: 1.), (x: 2., y: 1.), (x: 2., y: 2.), (x: 1., y: 1.)];
+        let polygon = Polygon::new(LineString(Vec::new()), vec![hole.clone()]);
+
+        let shape = XgShape::from(Geometry::Polygon(polygon.clone()));
+        match &shape {
+            XgShape::Polygon(rings) => {
+                assert_eq!(rings.len(), 2);
+                assert!(rings[0].is_empty());
+            }
+            _ => panic!("{shape:?}"),
+        }
+
+        round_trip(Geometry::Polygon(polygon));
+        assert_eq!(
+            Geometry::from(shape),
+            Geometry::Polygon(Polygon::new(LineString(Vec::new()), vec![hole]))
+        );
+    }
+
+    #[test]
+    fn derived_shapes() {
+        let rect = geo_types::Rect::new(coord! { x: 0., y: 0. }, coord! { x: 1., y: 2. });
+        assert_eq!(
+            XgShape::from(Geometry::Rect(rect)),
+            XgShape::from(Geometry::Polygon(rect.to_polygon()))
+        );
+        let line = geo_types::Line::new(coord! { x: 0., y: 0. }, coord! { x: 1., y: 2. });
+        assert_eq!(
+            XgShape::from(Geometry::Line(line)),
+            XgShape::LineString(vec![XgCoord::new(0., 0.), XgCoord::new(1., 2.)])
+        );
+    }
+}
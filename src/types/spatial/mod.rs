@@ -0,0 +1,491 @@
+mod wkb;
+mod wkt;
+
+#[cfg(feature = "geo")]
+mod geo;
+
+use crate::arguments::XuguArgumentValue;
+use crate::protocol::text::ColumnType;
+use crate::{Xugu, XuguTypeInfo, XuguValueRef};
+use sqlx_core::decode::Decode;
+use sqlx_core::encode::{Encode, IsNull};
+use sqlx_core::error::BoxDynError;
+use sqlx_core::types::Type;
+use std::borrow::Cow;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// 二维坐标
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct XgCoord {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl XgCoord {
+    pub const fn new(x: f64, y: f64) -> Self {
+        Self { x, y }
+    }
+}
+
+/// OGC 简单要素模型中的几何图形
+///
+/// 多边形的每个环为首尾相同的闭合折线，第一个环为外环，其余为内环（洞）。
+#[derive(Debug, Clone, PartialEq)]
+pub enum XgShape {
+    Point(XgCoord),
+    LineString(Vec<XgCoord>),
+    Polygon(Vec<Vec<XgCoord>>),
+    MultiPoint(Vec<XgCoord>),
+    MultiLineString(Vec<Vec<XgCoord>>),
+    MultiPolygon(Vec<Vec<Vec<XgCoord>>>),
+    GeometryCollection(Vec<XgShape>),
+}
+
+/// ## Xugu GEOMETRY / GEOGRAPHY type
+///
+/// 空间扩展中的 OGC 几何对象，带可选的空间参考标识（SRID），
+/// 可以读写 `GEOMETRY` 和 `GEOGRAPHY` 列。
+///
+/// 支持以下格式，只支持二维坐标，带 Z、M 坐标的图形会返回错误；
+/// 空点（`POINT EMPTY`，即 WKB 中两个坐标都为 NaN 的点）在两种格式中都不支持：
+/// * WKT：`POINT(1 2)`，见 [`XgGeometry::to_wkt`]
+/// * EWKT：`SRID=4326;POINT(1 2)`，即 [`Display`] 和 [`FromStr`] 的格式
+/// * WKB 和 EWKB：大端序和小端序均可解析，见 [`XgGeometry::from_wkb`]
+///
+/// 编码时按 EWKT 发送；解码时按服务器返回的内容识别 (E)WKB、十六进制 (E)WKB 或 (E)WKT。
+///
+/// ```rust
+/// # use sqlx_xugu::types::{XgCoord, XgGeometry, XgShape};
+/// let geom: XgGeometry = "SRID=4326;LINESTRING(116.4 39.9, 121.5 31.2)".parse().unwrap();
+/// assert_eq!(geom.srid, Some(4326));
+/// assert_eq!(
+///     geom.shape,
+///     XgShape::LineString(vec![XgCoord::new(116.4, 39.9), XgCoord::new(121.5, 31.2)])
+/// );
+///
+/// let wkb = geom.to_ewkb();
+/// assert_eq!(XgGeometry::from_wkb(&wkb).unwrap(), geom);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct XgGeometry {
+    pub srid: Option<u32>,
+    pub shape: XgShape,
+}
+
+impl XgGeometry {
+    pub fn new(shape: XgShape) -> Self {
+        Self { srid: None, shape }
+    }
+
+    pub fn with_srid(shape: XgShape, srid: u32) -> Self {
+        Self {
+            srid: Some(srid),
+            shape,
+        }
+    }
+
+    /// WKT 格式，不包含 SRID
+    pub fn to_wkt(&self) -> String {
+        let mut s = String::new();
+        wkt::write_shape(&mut s, &self.shape);
+        s
+    }
+
+    /// WKB 格式（小端序），不包含 SRID
+    pub fn to_wkb(&self) -> Vec<u8> {
+        wkb::write(&self.shape, None)
+    }
+
+    /// EWKB 格式（小端序），包含 SRID
+    pub fn to_ewkb(&self) -> Vec<u8> {
+        wkb::write(&self.shape, self.srid)
+    }
+
+    /// 解析 WKB 或 EWKB
+    pub fn from_wkb(buf: &[u8]) -> Result<Self, BoxDynError> {
+        wkb::read(buf)
+    }
+}
+
+impl From<XgShape> for XgGeometry {
+    fn from(shape: XgShape) -> Self {
+        Self::new(shape)
+    }
+}
+
+impl Type<Xugu> for XgGeometry {
+    fn type_info() -> XuguTypeInfo {
+        XuguTypeInfo::binary(ColumnType::GEOMETRY)
+    }
+
+    fn compatible(ty: &XuguTypeInfo) -> bool {
+        matches!(
+            ty.r#type,
+            ColumnType::GEOMETRY | ColumnType::GEOGRAPHY | ColumnType::CHAR | ColumnType::BINARY
+        )
+    }
+}
+
+impl Encode<'_, Xugu> for XgGeometry {
+    fn encode_by_ref(&self, args: &mut Vec<XuguArgumentValue>) -> Result<IsNull, BoxDynError> {
+        args.push(XuguArgumentValue::Str(Cow::Owned(self.to_string())));
+
+        Ok(IsNull::No)
+    }
+
+    fn produces(&self) -> Option<XuguTypeInfo> {
+        Some(XuguTypeInfo::binary(ColumnType::CHAR))
+    }
+}
+
+impl<'r> Decode<'r, Xugu> for XgGeometry {
+    fn decode(value: XuguValueRef<'r>) -> Result<Self, BoxDynError> {
+        let buf = value.as_bytes()?;
+        // WKB 的第一个字节为字节序标记
+        if matches!(buf.first(), Some(0 | 1)) {
+            return Self::from_wkb(buf);
+        }
+
+        let s = std::str::from_utf8(buf)?.trim();
+        if !s.is_empty() && s.len() % 2 == 0 && s.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Self::from_wkb(&wkb::decode_hex(s)?);
+        }
+
+        Self::from_str(s)
+    }
+}
+
+/// EWKT 格式，没有 SRID 时与 WKT 相同
+impl Display for XgGeometry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(srid) = self.srid {
+            write!(f, "SRID={srid};")?;
+        }
+        f.write_str(&self.to_wkt())
+    }
+}
+
+/// 解析 WKT 或 EWKT
+impl FromStr for XgGeometry {
+    type Err = BoxDynError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (srid, wkt) = match s.get(..5) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("SRID=") => {
+                let (srid, wkt) = s[5..]
+                    .split_once(';')
+                    .ok_or_else(|| format!("invalid EWKT {s:?}: missing `;` after SRID"))?;
+                let srid = srid
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("invalid EWKT {s:?}: invalid SRID"))?;
+                (Some(srid), wkt)
+            }
+            _ => (None, s),
+        };
+
+        Ok(Self {
+            srid,
+            shape: wkt::parse(wkt)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::text::ColumnFlags;
+
+    fn c(x: f64, y: f64) -> XgCoord {
+        XgCoord::new(x, y)
+    }
+
+    fn shapes() -> Vec<XgShape> {
+        let ring = vec![c(0., 0.), c(4., 0.), c(4., 4.), c(0., 0.)];
+        let hole = vec![c(1., 1.), c(2., 1.), c(2., 2.), c(1., 1.)];
+        vec![
+            XgShape::Point(c(1.5, -2.25)),
+            XgShape::Point(c(f64::NAN, 0.)),
+            XgShape::LineString(vec![c(0., 0.), c(1e-300, 1e300)]),
+            XgShape::LineString(Vec::new()),
+            XgShape::Polygon(vec![ring.clone(), hole.clone()]),
+            XgShape::Polygon(vec![Vec::new(), hole.clone()]),
+            XgShape::Polygon(Vec::new()),
+            XgShape::MultiPoint(vec![c(1., 2.), c(3., 4.)]),
+            XgShape::MultiLineString(vec![vec![c(1., 2.), c(3., 4.)], Vec::new()]),
+            XgShape::MultiPolygon(vec![vec![ring.clone()], Vec::new(), vec![ring, hole]]),
+            XgShape::GeometryCollection(vec![
+                XgShape::Point(c(1., 2.)),
+                XgShape::GeometryCollection(Vec::new()),
+                XgShape::MultiPoint(Vec::new()),
+            ]),
+            XgShape::GeometryCollection(Vec::new()),
+        ]
+    }
+
+    /// NaN 不等于自身，按 WKT 文本比较
+    fn assert_same(a: &XgGeometry, b: &XgGeometry) {
+        assert_eq!(a.srid, b.srid);
+        assert_eq!(a.to_wkt(), b.to_wkt());
+    }
+
+    #[test]
+    fn wkt_and_wkb_round_trip() {
+        for shape in shapes() {
+            for geometry in [
+                XgGeometry::new(shape.clone()),
+                XgGeometry::with_srid(shape.clone(), 4326),
+            ] {
+                let text = geometry.to_string();
+                assert_same(&text.parse().unwrap(), &geometry);
+                assert_same(
+                    &XgGeometry::from_wkb(&geometry.to_ewkb()).unwrap(),
+                    &geometry,
+                );
+
+                let wkb = XgGeometry::from_wkb(&geometry.to_wkb()).unwrap();
+                assert_same(&wkb, &XgGeometry::new(shape.clone()));
+            }
+        }
+    }
+
+    #[test]
+    fn parse_wkt_variants() {
+        let cases = [
+            ("point ( 1 2 )", XgShape::Point(c(1., 2.))),
+            ("POINT(1e2 -.5)", XgShape::Point(c(100., -0.5))),
+            (
+                "MULTIPOINT(1 2, 3 4)",
+                XgShape::MultiPoint(vec![c(1., 2.), c(3., 4.)]),
+            ),
+            (
+                "MULTIPOINT((1 2),(3 4))",
+                XgShape::MultiPoint(vec![c(1., 2.), c(3., 4.)]),
+            ),
+            ("LINESTRING EMPTY", XgShape::LineString(Vec::new())),
+            (
+                "GeometryCollection empty",
+                XgShape::GeometryCollection(Vec::new()),
+            ),
+        ];
+        for (text, shape) in cases {
+            assert_eq!(text.parse::<XgGeometry>().unwrap().shape, shape, "{text}");
+        }
+
+        let geometry: XgGeometry = " srid=3857 ; POINT(1 2)".parse().unwrap();
+        assert_eq!(geometry.srid, Some(3857));
+    }
+
+    #[test]
+    fn parse_wkt_rejects_malformed() {
+        for text in [
+            "",
+            "POINT",
+            "POINT EMPTY",
+            "POINT(1)",
+            "POINT(1 2",
+            "POINT(1 2 3)",
+            "POINT Z(1 2 3)",
+            "POINTM(1 2 3)",
+            "LINESTRING(1 2,)",
+            "LINESTRING(1 2) x",
+            "CIRCLE(1 2)",
+            "SRID=4326POINT(1 2)",
+            "SRID=x;POINT(1 2)",
+        ] {
+            assert!(text.parse::<XgGeometry>().is_err(), "{text}");
+        }
+    }
+
+    #[test]
+    fn nan_points_rejected_by_both_formats() {
+        // WKB 中两个坐标都为 NaN 的点表示空点
+        for text in [
+            "POINT(NaN NaN)",
+            "MULTIPOINT(1 2,NaN NaN)",
+            "MULTIPOINT((NaN NaN))",
+        ] {
+            assert!(text.parse::<XgGeometry>().is_err(), "{text}");
+        }
+        for shape in [
+            XgShape::Point(c(f64::NAN, f64::NAN)),
+            XgShape::MultiPoint(vec![c(f64::NAN, f64::NAN)]),
+        ] {
+            assert!(XgGeometry::from_wkb(&XgGeometry::new(shape).to_wkb()).is_err());
+        }
+
+        // 只有一个坐标为 NaN 的点，以及折线中的 NaN 坐标，两种格式都接受
+        for text in ["POINT(NaN 1)", "LINESTRING(NaN NaN,1 2)"] {
+            let geometry: XgGeometry = text.parse().unwrap();
+            assert_same(
+                &XgGeometry::from_wkb(&geometry.to_wkb()).unwrap(),
+                &geometry,
+            );
+        }
+    }
+
+    /// 大端序的 WKB 头部
+    fn be_header(buf: &mut Vec<u8>, ty: u32) {
+        buf.push(0);
+        buf.extend(ty.to_be_bytes());
+    }
+
+    fn be_coord(buf: &mut Vec<u8>, x: f64, y: f64) {
+        buf.extend(x.to_be_bytes());
+        buf.extend(y.to_be_bytes());
+    }
+
+    #[test]
+    fn read_big_endian_and_srid_flag() {
+        let mut buf = Vec::new();
+        be_header(&mut buf, 1);
+        be_coord(&mut buf, 1., 2.);
+        assert_eq!(
+            XgGeometry::from_wkb(&buf).unwrap(),
+            XgGeometry::new(XgShape::Point(c(1., 2.)))
+        );
+
+        // EWKB：类型带 SRID 标志位，随后是 SRID
+        let mut buf = Vec::new();
+        be_header(&mut buf, 0x2000_0002);
+        buf.extend(4326_u32.to_be_bytes());
+        buf.extend(2_u32.to_be_bytes());
+        be_coord(&mut buf, 1., 2.);
+        be_coord(&mut buf, 3., 4.);
+        assert_eq!(
+            XgGeometry::from_wkb(&buf).unwrap(),
+            XgGeometry::with_srid(XgShape::LineString(vec![c(1., 2.), c(3., 4.)]), 4326)
+        );
+
+        // 小端序的集合中包含大端序的元素
+        let mut buf = vec![1];
+        buf.extend(4_u32.to_le_bytes());
+        buf.extend(2_u32.to_le_bytes());
+        be_header(&mut buf, 1);
+        be_coord(&mut buf, 1., 2.);
+        buf.push(1);
+        buf.extend(1_u32.to_le_bytes());
+        buf.extend(3_f64.to_le_bytes());
+        buf.extend(4_f64.to_le_bytes());
+        assert_eq!(
+            XgGeometry::from_wkb(&buf).unwrap().shape,
+            XgShape::MultiPoint(vec![c(1., 2.), c(3., 4.)])
+        );
+
+        // 写出的 EWKB 为小端序，带 SRID 标志位
+        let ewkb = XgGeometry::with_srid(XgShape::Point(c(1., 2.)), 4326).to_ewkb();
+        assert_eq!(ewkb[..9], [1, 1, 0, 0, 0x20, 0xe6, 0x10, 0, 0]);
+    }
+
+    #[test]
+    fn read_wkb_rejects_malformed() {
+        let mut z_point = Vec::new();
+        be_header(&mut z_point, 0x8000_0001);
+        be_coord(&mut z_point, 1., 2.);
+        z_point.extend(3_f64.to_be_bytes());
+
+        let mut iso_z_point = Vec::new();
+        be_header(&mut iso_z_point, 1001);
+        be_coord(&mut iso_z_point, 1., 2.);
+        iso_z_point.extend(3_f64.to_be_bytes());
+
+        // MULTIPOINT 中的折线
+        let mut wrong_part = Vec::new();
+        be_header(&mut wrong_part, 4);
+        wrong_part.extend(1_u32.to_be_bytes());
+        be_header(&mut wrong_part, 2);
+        wrong_part.extend(0_u32.to_be_bytes());
+
+        // 声明了大量元素，但没有数据
+        let mut huge_count = Vec::new();
+        be_header(&mut huge_count, 2);
+        huge_count.extend(u32::MAX.to_be_bytes());
+
+        let mut unknown = Vec::new();
+        be_header(&mut unknown, 17);
+
+        let mut bad_order = XgGeometry::new(XgShape::Point(c(1., 2.))).to_wkb();
+        bad_order[0] = 2;
+
+        let mut trailing = XgGeometry::new(XgShape::Point(c(1., 2.))).to_wkb();
+        trailing.push(0);
+
+        for (name, buf) in [
+            ("empty", Vec::new()),
+            ("z", z_point),
+            ("iso z", iso_z_point),
+            ("wrong part", wrong_part),
+            ("huge count", huge_count),
+            ("unknown", unknown),
+            ("byte order", bad_order),
+            ("trailing", trailing),
+        ] {
+            assert!(XgGeometry::from_wkb(&buf).is_err(), "{name}");
+        }
+    }
+
+    #[test]
+    fn read_wkb_rejects_truncated() {
+        for shape in shapes() {
+            let ewkb = XgGeometry::with_srid(shape, 4326).to_ewkb();
+            for len in 0..ewkb.len() {
+                assert!(XgGeometry::from_wkb(&ewkb[..len]).is_err(), "{len}");
+            }
+        }
+    }
+
+    fn nested(depth: usize) -> XgShape {
+        (0..depth).fold(XgShape::Point(c(1., 2.)), |shape, _| {
+            XgShape::GeometryCollection(vec![shape])
+        })
+    }
+
+    #[test]
+    fn nesting_depth_is_limited() {
+        let geometry = XgGeometry::new(nested(wkb::MAX_DEPTH));
+        assert_eq!(
+            geometry.to_string().parse::<XgGeometry>().unwrap(),
+            geometry
+        );
+        assert_eq!(XgGeometry::from_wkb(&geometry.to_wkb()).unwrap(), geometry);
+
+        let geometry = XgGeometry::new(nested(wkb::MAX_DEPTH + 1));
+        assert!(geometry.to_string().parse::<XgGeometry>().is_err());
+        assert!(XgGeometry::from_wkb(&geometry.to_wkb()).is_err());
+
+        // 不会因为过深的嵌套而栈溢出
+        let text = "GEOMETRYCOLLECTION(".repeat(100_000);
+        assert!(text.parse::<XgGeometry>().is_err());
+    }
+
+    fn decode(ty: ColumnType, bytes: &[u8]) -> Result<XgGeometry, BoxDynError> {
+        XgGeometry::decode(XuguValueRef {
+            value: Some(bytes),
+            row: None,
+            type_info: XuguTypeInfo {
+                r#type: ty,
+                flags: ColumnFlags::empty(),
+            },
+            time_zone: None,
+        })
+    }
+
+    #[test]
+    fn decode_server_formats() {
+        let geometry = XgGeometry::with_srid(XgShape::Point(c(1., 2.)), 4326);
+        let ewkb = geometry.to_ewkb();
+        let hex: String = ewkb.iter().map(|b| format!("{b:02X}")).collect();
+
+        assert_eq!(decode(ColumnType::GEOMETRY, &ewkb).unwrap(), geometry);
+        assert_eq!(
+            decode(ColumnType::GEOMETRY, hex.as_bytes()).unwrap(),
+            geometry
+        );
+        assert_eq!(
+            decode(ColumnType::CHAR, b"SRID=4326;POINT(1 2)").unwrap(),
+            geometry
+        );
+    }
+}
@@ -0,0 +1,281 @@
+use super::{XgCoord, XgGeometry, XgShape};
+use bytes::BufMut;
+use sqlx_core::error::BoxDynError;
+
+const WKB_POINT: u32 = 1;
+const WKB_LINESTRING: u32 = 2;
+const WKB_POLYGON: u32 = 3;
+const WKB_MULTIPOINT: u32 = 4;
+const WKB_MULTILINESTRING: u32 = 5;
+const WKB_MULTIPOLYGON: u32 = 6;
+const WKB_GEOMETRYCOLLECTION: u32 = 7;
+
+/// EWKB 类型中的标志位
+const EWKB_Z: u32 = 0x8000_0000;
+const EWKB_M: u32 = 0x4000_0000;
+const EWKB_SRID: u32 = 0x2000_0000;
+
+/// `GEOMETRYCOLLECTION` 的最大嵌套层数
+pub(super) const MAX_DEPTH: usize = 32;
+
+/// 按小端序写出，`srid` 不为空时写出 EWKB
+pub(super) fn write(shape: &XgShape, srid: Option<u32>) -> Vec<u8> {
+    let mut buf = Vec::new();
+    write_shape(&mut buf, shape, srid);
+    buf
+}
+
+fn write_header(buf: &mut Vec<u8>, ty: u32, srid: Option<u32>) {
+    buf.put_u8(1);
+    match srid {
+        Some(srid) => {
+            buf.put_u32_le(ty | EWKB_SRID);
+            buf.put_u32_le(srid);
+        }
+        None => buf.put_u32_le(ty),
+    }
+}
+
+fn write_coords(buf: &mut Vec<u8>, coords: &[XgCoord]) {
+    buf.put_u32_le(coords.len() as u32);
+    for c in coords {
+        buf.put_f64_le(c.x);
+        buf.put_f64_le(c.y);
+    }
+}
+
+fn write_rings(buf: &mut Vec<u8>, rings: &[Vec<XgCoord>]) {
+    buf.put_u32_le(rings.len() as u32);
+    for ring in rings {
+        write_coords(buf, ring);
+    }
+}
+
+fn write_shape(buf: &mut Vec<u8>, shape: &XgShape, srid: Option<u32>) {
+    match shape {
+        XgShape::Point(c) => {
+            write_header(buf, WKB_POINT, srid);
+            buf.put_f64_le(c.x);
+            buf.put_f64_le(c.y);
+        }
+        XgShape::LineString(line) => {
+            write_header(buf, WKB_LINESTRING, srid);
+            write_coords(buf, line);
+        }
+        XgShape::Polygon(rings) => {
+            write_header(buf, WKB_POLYGON, srid);
+            write_rings(buf, rings);
+        }
+        XgShape::MultiPoint(points) => {
+            write_header(buf, WKB_MULTIPOINT, srid);
+            buf.put_u32_le(points.len() as u32);
+            for c in points {
+                write_shape(buf, &XgShape::Point(*c), None);
+            }
+        }
+        XgShape::MultiLineString(lines) => {
+            write_header(buf, WKB_MULTILINESTRING, srid);
+            buf.put_u32_le(lines.len() as u32);
+            for line in lines {
+                write_header(buf, WKB_LINESTRING, None);
+                write_coords(buf, line);
+            }
+        }
+        XgShape::MultiPolygon(polygons) => {
+            write_header(buf, WKB_MULTIPOLYGON, srid);
+            buf.put_u32_le(polygons.len() as u32);
+            for rings in polygons {
+                write_header(buf, WKB_POLYGON, None);
+                write_rings(buf, rings);
+            }
+        }
+        XgShape::GeometryCollection(shapes) => {
+            write_header(buf, WKB_GEOMETRYCOLLECTION, srid);
+            buf.put_u32_le(shapes.len() as u32);
+            for shape in shapes {
+                write_shape(buf, shape, None);
+            }
+        }
+    }
+}
+
+/// 解析 WKB 或 EWKB，每个几何对象可以使用不同的字节序
+pub(super) fn read(buf: &[u8]) -> Result<XgGeometry, BoxDynError> {
+    let mut reader = Reader {
+        buf,
+        little_endian: true,
+        depth: 0,
+    };
+    let (shape, srid) = reader.shape(None)?;
+    if !reader.buf.is_empty() {
+        return Err(format!("{} trailing bytes after WKB geometry", reader.buf.len()).into());
+    }
+
+    Ok(XgGeometry { srid, shape })
+}
+
+pub(super) fn decode_hex(s: &str) -> Result<Vec<u8>, BoxDynError> {
+    (0..s.len())
+        .step_by(2)
+        .map(|i| {
+            s.get(i..i + 2)
+                .and_then(|b| u8::from_str_radix(b, 16).ok())
+                .ok_or_else(|| format!("invalid hex WKB {s:?}").into())
+        })
+        .collect()
+}
+
+struct Reader<'a> {
+    buf: &'a [u8],
+    little_endian: bool,
+    depth: usize,
+}
+
+impl Reader<'_> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], BoxDynError> {
+        if self.buf.len() < N {
+            return Err("unexpected end of WKB".into());
+        }
+        let (head, rest) = self.buf.split_at(N);
+        self.buf = rest;
+        Ok(head.try_into().unwrap())
+    }
+
+    fn u32(&mut self) -> Result<u32, BoxDynError> {
+        let b = self.take::<4>()?;
+        Ok(if self.little_endian {
+            u32::from_le_bytes(b)
+        } else {
+            u32::from_be_bytes(b)
+        })
+    }
+
+    fn f64(&mut self) -> Result<f64, BoxDynError> {
+        let b = self.take::<8>()?;
+        Ok(if self.little_endian {
+            f64::from_le_bytes(b)
+        } else {
+            f64::from_be_bytes(b)
+        })
+    }
+
+    /// 元素个数，按剩余字节数限制预分配的大小
+    fn count(&mut self, min_size: usize) -> Result<(usize, usize), BoxDynError> {
+        let n = self.u32()? as usize;
+        Ok((n, n.min(self.buf.len() / min_size)))
+    }
+
+    fn coord(&mut self) -> Result<XgCoord, BoxDynError> {
+        Ok(XgCoord {
+            x: self.f64()?,
+            y: self.f64()?,
+        })
+    }
+
+    fn coords(&mut self) -> Result<Vec<XgCoord>, BoxDynError> {
+        let (n, cap) = self.count(16)?;
+        let mut coords = Vec::with_capacity(cap);
+        for _ in 0..n {
+            coords.push(self.coord()?);
+        }
+        Ok(coords)
+    }
+
+    fn rings(&mut self) -> Result<Vec<Vec<XgCoord>>, BoxDynError> {
+        let (n, cap) = self.count(4)?;
+        let mut rings = Vec::with_capacity(cap);
+        for _ in 0..n {
+            rings.push(self.coords()?);
+        }
+        Ok(rings)
+    }
+
+    /// 读取一个几何对象，`expected` 为多部件几何对象中要求的元素类型
+    fn shape(&mut self, expected: Option<u32>) -> Result<(XgShape, Option<u32>), BoxDynError> {
+        self.little_endian = match self.take::<1>()?[0] {
+            0 => false,
+            1 => true,
+            b => return Err(format!("invalid WKB byte order {b}").into()),
+        };
+
+        let ty = self.u32()?;
+        if ty & (EWKB_Z | EWKB_M) != 0 || ty & 0xffff > 1000 {
+            return Err("only two-dimensional geometries are supported".into());
+        }
+        let srid = match ty & EWKB_SRID {
+            0 => None,
+            _ => Some(self.u32()?),
+        };
+        let ty = ty & 0xffff;
+        if expected.is_some_and(|expected| expected != ty) {
+            return Err(format!("unexpected WKB geometry type {ty} in a multi geometry").into());
+        }
+
+        let shape = match ty {
+            WKB_POINT => {
+                let c = self.coord()?;
+                if c.x.is_nan() && c.y.is_nan() {
+                    return Err("empty points are not supported".into());
+                }
+                XgShape::Point(c)
+            }
+            WKB_LINESTRING => XgShape::LineString(self.coords()?),
+            WKB_POLYGON => XgShape::Polygon(self.rings()?),
+            WKB_MULTIPOINT => XgShape::MultiPoint(
+                self.parts(WKB_POINT, 21)?
+                    .into_iter()
+                    .map(|shape| match shape {
+                        XgShape::Point(c) => c,
+                        _ => unreachable!(),
+                    })
+                    .collect(),
+            ),
+            WKB_MULTILINESTRING => XgShape::MultiLineString(
+                self.parts(WKB_LINESTRING, 9)?
+                    .into_iter()
+                    .map(|shape| match shape {
+                        XgShape::LineString(line) => line,
+                        _ => unreachable!(),
+                    })
+                    .collect(),
+            ),
+            WKB_MULTIPOLYGON => XgShape::MultiPolygon(
+                self.parts(WKB_POLYGON, 9)?
+                    .into_iter()
+                    .map(|shape| match shape {
+                        XgShape::Polygon(rings) => rings,
+                        _ => unreachable!(),
+                    })
+                    .collect(),
+            ),
+            WKB_GEOMETRYCOLLECTION => XgShape::GeometryCollection(self.parts_any()?),
+            _ => return Err(format!("unknown WKB geometry type {ty}").into()),
+        };
+
+        Ok((shape, srid))
+    }
+
+    fn parts(&mut self, ty: u32, min_size: usize) -> Result<Vec<XgShape>, BoxDynError> {
+        let (n, cap) = self.count(min_size)?;
+        let mut parts = Vec::with_capacity(cap);
+        for _ in 0..n {
+            parts.push(self.shape(Some(ty))?.0);
+        }
+        Ok(parts)
+    }
+
+    fn parts_any(&mut self) -> Result<Vec<XgShape>, BoxDynError> {
+        if self.depth == MAX_DEPTH {
+            return Err("WKB geometry collections are nested too deeply".into());
+        }
+
+        let (n, cap) = self.count(9)?;
+        let mut parts = Vec::with_capacity(cap);
+        self.depth += 1;
+        for _ in 0..n {
+            parts.push(self.shape(None)?.0);
+        }
+        self.depth -= 1;
+        Ok(parts)
+    }
+}
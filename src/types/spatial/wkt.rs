@@ -0,0 +1,247 @@
+use super::wkb::MAX_DEPTH;
+use super::{XgCoord, XgShape};
+use sqlx_core::error::BoxDynError;
+use std::fmt::Write;
+
+pub(super) fn write_shape(s: &mut String, shape: &XgShape) {
+    match shape {
+        XgShape::Point(c) => {
+            s.push_str("POINT(");
+            write_coord(s, c);
+            s.push(')');
+        }
+        XgShape::LineString(line) => {
+            s.push_str("LINESTRING");
+            write_coords(s, line);
+        }
+        XgShape::Polygon(rings) => {
+            s.push_str("POLYGON");
+            write_list(s, rings, |s, ring| write_coords(s, ring));
+        }
+        XgShape::MultiPoint(points) => {
+            s.push_str("MULTIPOINT");
+            write_list(s, points, |s, c| {
+                s.push('(');
+                write_coord(s, c);
+                s.push(')');
+            });
+        }
+        XgShape::MultiLineString(lines) => {
+            s.push_str("MULTILINESTRING");
+            write_list(s, lines, |s, line| write_coords(s, line));
+        }
+        XgShape::MultiPolygon(polygons) => {
+            s.push_str("MULTIPOLYGON");
+            write_list(s, polygons, |s, rings| {
+                write_list(s, rings, |s, ring| write_coords(s, ring))
+            });
+        }
+        XgShape::GeometryCollection(shapes) => {
+            s.push_str("GEOMETRYCOLLECTION");
+            write_list(s, shapes, write_shape);
+        }
+    }
+}
+
+fn write_coord(s: &mut String, c: &XgCoord) {
+    let _ = write!(s, "{} {}", c.x, c.y);
+}
+
+fn write_coords(s: &mut String, coords: &[XgCoord]) {
+    write_list(s, coords, write_coord);
+}
+
+/// 空列表输出为 ` EMPTY`
+fn write_list<T>(s: &mut String, items: &[T], mut f: impl FnMut(&mut String, &T)) {
+    if items.is_empty() {
+        s.push_str(" EMPTY");
+        return;
+    }
+
+    s.push('(');
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            s.push(',');
+        }
+        f(s, item);
+    }
+    s.push(')');
+}
+
+pub(super) fn parse(wkt: &str) -> Result<XgShape, BoxDynError> {
+    let mut parser = Parser {
+        s: wkt,
+        pos: 0,
+        depth: 0,
+    };
+    let shape = parser.shape()?;
+    parser.skip_ws();
+    if parser.pos != wkt.len() {
+        return Err(parser.error("unexpected trailing characters"));
+    }
+
+    Ok(shape)
+}
+
+struct Parser<'a> {
+    s: &'a str,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, msg: &str) -> BoxDynError {
+        format!("invalid WKT {:?} at byte {}: {msg}", self.s, self.pos).into()
+    }
+
+    fn skip_ws(&mut self) {
+        let rest = &self.s[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_ws();
+        self.s[self.pos..].chars().next()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), BoxDynError> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected `{c}`")))
+        }
+    }
+
+    fn word(&mut self) -> String {
+        self.skip_ws();
+        let rest = &self.s[self.pos..];
+        let len = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        self.pos += len;
+        rest[..len].to_ascii_uppercase()
+    }
+
+    /// 读取 `EMPTY`，没有时不移动位置
+    fn empty(&mut self) -> bool {
+        let pos = self.pos;
+        if self.word() == "EMPTY" {
+            return true;
+        }
+        self.pos = pos;
+        false
+    }
+
+    fn number(&mut self) -> Result<f64, BoxDynError> {
+        self.skip_ws();
+        let rest = &self.s[self.pos..];
+        let len = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+')))
+            .unwrap_or(rest.len());
+        let n = rest[..len]
+            .parse()
+            .map_err(|_| self.error("expected a number"))?;
+        self.pos += len;
+        Ok(n)
+    }
+
+    fn coord(&mut self) -> Result<XgCoord, BoxDynError> {
+        let x = self.number()?;
+        let y = self.number()?;
+        if !matches!(self.peek(), Some(',' | ')')) {
+            return Err(self.error("only two-dimensional coordinates are supported"));
+        }
+        Ok(XgCoord { x, y })
+    }
+
+    /// 点的坐标；与 WKB 一致，两个坐标都为 NaN 的点表示空点，不支持
+    fn point(&mut self) -> Result<XgCoord, BoxDynError> {
+        let c = self.coord()?;
+        if c.x.is_nan() && c.y.is_nan() {
+            return Err(self.error("empty points are not supported"));
+        }
+        Ok(c)
+    }
+
+    /// `EMPTY` 或 `( item, ... )`
+    fn list<T>(
+        &mut self,
+        mut item: impl FnMut(&mut Self) -> Result<T, BoxDynError>,
+    ) -> Result<Vec<T>, BoxDynError> {
+        if self.empty() {
+            return Ok(Vec::new());
+        }
+
+        self.expect('(')?;
+        let mut items = vec![item(self)?];
+        while self.eat(',') {
+            items.push(item(self)?);
+        }
+        self.expect(')')?;
+        Ok(items)
+    }
+
+    fn coords(&mut self) -> Result<Vec<XgCoord>, BoxDynError> {
+        self.list(Self::coord)
+    }
+
+    fn rings(&mut self) -> Result<Vec<Vec<XgCoord>>, BoxDynError> {
+        self.list(Self::coords)
+    }
+
+    fn shape(&mut self) -> Result<XgShape, BoxDynError> {
+        let kind = self.word();
+        let pos = self.pos;
+        if matches!(self.word().as_str(), "Z" | "M" | "ZM") {
+            return Err(self.error("only two-dimensional geometries are supported"));
+        }
+        self.pos = pos;
+
+        let shape = match kind.as_str() {
+            "POINT" => {
+                if self.empty() {
+                    return Err(self.error("empty points are not supported"));
+                }
+                self.expect('(')?;
+                let c = self.point()?;
+                self.expect(')')?;
+                XgShape::Point(c)
+            }
+            "LINESTRING" => XgShape::LineString(self.coords()?),
+            "POLYGON" => XgShape::Polygon(self.rings()?),
+            // 点可以带括号，也可以不带：MULTIPOINT((1 2),(3 4)) 或 MULTIPOINT(1 2,3 4)
+            "MULTIPOINT" => XgShape::MultiPoint(self.list(|p| {
+                if p.eat('(') {
+                    let c = p.point()?;
+                    p.expect(')')?;
+                    Ok(c)
+                } else {
+                    p.point()
+                }
+            })?),
+            "MULTILINESTRING" => XgShape::MultiLineString(self.rings()?),
+            "MULTIPOLYGON" => XgShape::MultiPolygon(self.list(Self::rings)?),
+            "GEOMETRYCOLLECTION" => {
+                if self.depth == MAX_DEPTH {
+                    return Err(self.error("geometry collections are nested too deeply"));
+                }
+                self.depth += 1;
+                let shapes = self.list(Self::shape)?;
+                self.depth -= 1;
+                XgShape::GeometryCollection(shapes)
+            }
+            _ => return Err(self.error("unknown geometry type")),
+        };
+
+        Ok(shape)
+    }
+}
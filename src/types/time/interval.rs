@@ -1,5 +1,6 @@
 use crate::arguments::XuguArgumentValue;
 use crate::protocol::text::ColumnType;
+use crate::types::XgInterval;
 use crate::{Xugu, XuguTypeInfo, XuguValueRef};
 use bytes::Buf;
 use sqlx_core::decode::Decode;
@@ -98,3 +99,29 @@ impl<'r> Decode<'r, Xugu> for Duration {
         }
     }
 }
+
+impl TryFrom<Duration> for XgInterval {
+    type Error = BoxDynError;
+
+    fn try_from(value: Duration) -> Result<Self, Self::Error> {
+        let micros = value
+            .whole_microseconds()
+            .try_into()
+            .map_err(|_| format!("value {value:?} overflows Xugu INTERVAL DAY TO SECOND"))?;
+
+        Ok(XgInterval { months: 0, micros })
+    }
+}
+
+/// 年-月间隔的天数不固定，无法转换
+impl TryFrom<XgInterval> for Duration {
+    type Error = BoxDynError;
+
+    fn try_from(value: XgInterval) -> Result<Self, Self::Error> {
+        if value.months != 0 {
+            return Err(format!("interval {value} has a year-month part").into());
+        }
+
+        Ok(Duration::microseconds(value.micros))
+    }
+}
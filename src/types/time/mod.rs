@@ -2,6 +2,8 @@ mod interval;
 
 use crate::arguments::XuguArgumentValue;
 use crate::protocol::text::ColumnType;
+use crate::types::array::{array_type, non_null};
+use crate::types::XuguHasArrayType;
 use crate::{Xugu, XuguTypeInfo, XuguValueRef};
 
 use bytes::{Buf, BufMut};
@@ -10,6 +12,7 @@ use sqlx_core::encode::{Encode, IsNull};
 use sqlx_core::error::BoxDynError;
 use sqlx_core::types::Type;
 use std::borrow::Cow;
+use time::macros::format_description;
 use time::{Date, Duration, OffsetDateTime, PrimitiveDateTime, Time, UtcDateTime, UtcOffset};
 
 impl Type<Xugu> for OffsetDateTime {
@@ -46,7 +49,13 @@ impl<'r> Decode<'r, Xugu> for OffsetDateTime {
         let ty = value.type_info.r#type;
         // 不带时区的日期时间
         if ty == ColumnType::DATETIME || ty == ColumnType::DATE {
+            let time_zone = value.time_zone;
             let native = <PrimitiveDateTime as Decode<Xugu>>::decode(value)?;
+            // 优先使用会话时区，未知时使用本地时区
+            if let Some(offset) = time_zone {
+                let offset = UtcOffset::from_whole_seconds(offset)?;
+                return Ok(native.assume_offset(offset));
+            }
             let local = OffsetDateTime::now_local()?;
             let tz = local.replace_date_time(native);
 
@@ -209,3 +218,105 @@ impl<'r> Decode<'r, Xugu> for Time {
         Ok(time)
     }
 }
+
+impl XuguHasArrayType for Date {
+    fn array_type_info() -> XuguTypeInfo {
+        array_type(ColumnType::ARRAY_DATE)
+    }
+
+    fn encode_element(&self) -> Result<Option<String>, BoxDynError> {
+        Ok(Some(
+            self.format(format_description!("[year]-[month]-[day]"))?,
+        ))
+    }
+
+    fn decode_element(text: Option<&str>) -> Result<Self, BoxDynError> {
+        let format = format_description!("[year]-[month]-[day]");
+        Ok(Date::parse(non_null(text)?.trim(), format)?)
+    }
+}
+
+impl XuguHasArrayType for Time {
+    fn array_type_info() -> XuguTypeInfo {
+        array_type(ColumnType::ARRAY_TIME)
+    }
+
+    fn encode_element(&self) -> Result<Option<String>, BoxDynError> {
+        let format = format_description!("[hour]:[minute]:[second].[subsecond]");
+        Ok(Some(self.format(format)?))
+    }
+
+    fn decode_element(text: Option<&str>) -> Result<Self, BoxDynError> {
+        let format = format_description!("[hour]:[minute]:[second][optional [.[subsecond]]]");
+        Ok(Time::parse(non_null(text)?.trim(), format)?)
+    }
+}
+
+impl XuguHasArrayType for PrimitiveDateTime {
+    fn array_type_info() -> XuguTypeInfo {
+        array_type(ColumnType::ARRAY_DATETIME)
+    }
+
+    fn encode_element(&self) -> Result<Option<String>, BoxDynError> {
+        let format =
+            format_description!("[year]-[month]-[day] [hour]:[minute]:[second].[subsecond]");
+        Ok(Some(self.format(format)?))
+    }
+
+    fn decode_element(text: Option<&str>) -> Result<Self, BoxDynError> {
+        let format = format_description!(
+            "[year]-[month]-[day][first [ ][T]][hour]:[minute]:[second][optional [.[subsecond]]]"
+        );
+        Ok(PrimitiveDateTime::parse(non_null(text)?.trim(), format)?)
+    }
+}
+
+impl XuguHasArrayType for OffsetDateTime {
+    fn array_type_info() -> XuguTypeInfo {
+        array_type(ColumnType::ARRAY_DATETIME_TZ)
+    }
+
+    fn encode_element(&self) -> Result<Option<String>, BoxDynError> {
+        let format = format_description!(
+            "[year]-[month]-[day] [hour]:[minute]:[second].[subsecond] [offset_hour sign:mandatory]:[offset_minute]"
+        );
+        Ok(Some(self.format(format)?))
+    }
+
+    /// 日期和时间之间可以是空格或 `T`，时区前的空格可以省略，时区可以省略分钟
+    fn decode_element(text: Option<&str>) -> Result<Self, BoxDynError> {
+        let format = format_description!(
+            "[year]-[month]-[day][first [ ][T]][hour]:[minute]:[second][optional [.[subsecond]]][optional [ ]][offset_hour sign:mandatory][optional [:[offset_minute]]]"
+        );
+        Ok(OffsetDateTime::parse(non_null(text)?.trim(), format)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::array::{encode_array, parse_array};
+    use time::macros::datetime;
+
+    #[test]
+    fn offset_datetime_array_elements() {
+        let value = datetime!(2024-01-02 03:04:05.123456 +08:00);
+
+        let text = encode_array(&[Some(value), None]).unwrap();
+        assert_eq!(text, r#"{"2024-01-02 03:04:05.123456 +08:00",NULL}"#);
+        let elements = parse_array(&text).unwrap();
+        let decoded = OffsetDateTime::decode_element(elements[0].as_deref()).unwrap();
+        assert_eq!(decoded, value);
+        assert_eq!(decoded.offset(), value.offset());
+
+        for text in [
+            "2024-01-01T19:04:05.123456+00:00",
+            "2024-01-02 03:04:05.123456+08",
+            "2024-01-02 03:04:05.123456 +08",
+        ] {
+            assert_eq!(OffsetDateTime::decode_element(Some(text)).unwrap(), value);
+        }
+
+        assert!(OffsetDateTime::decode_element(Some("2024-01-02 03:04:05")).is_err());
+    }
+}
@@ -1,6 +1,9 @@
 use crate::arguments::XuguArgumentValue;
 use crate::error::BoxDynError;
 use crate::protocol::text::{ColumnFlags, ColumnType};
+use crate::types::array::{array_type, is_collection, non_null};
+use crate::types::numeric::decode_wide_integer;
+use crate::types::XuguHasArrayType;
 use crate::{Xugu, XuguTypeInfo, XuguValueRef};
 use byteorder::{BigEndian, ByteOrder};
 use sqlx_core::decode::Decode;
@@ -49,13 +52,14 @@ impl Type<Xugu> for u32 {
     }
 }
 
+/// 大于 `i64::MAX` 的值超出 `BIGINT` 的范围，需要使用 `NUMERIC` 列
 impl Type<Xugu> for u64 {
     fn type_info() -> XuguTypeInfo {
         XuguTypeInfo::binary(ColumnType::BIGINT)
     }
 
     fn compatible(ty: &XuguTypeInfo) -> bool {
-        uint_compatible(ty)
+        uint_compatible(ty) || ty.r#type == ColumnType::NUMERIC
     }
 }
 
@@ -88,11 +92,21 @@ impl Encode<'_, Xugu> for u32 {
 
 impl Encode<'_, Xugu> for u64 {
     fn encode_by_ref(&self, args: &mut Vec<XuguArgumentValue<'_>>) -> Result<IsNull, BoxDynError> {
+        // 超出 BIGINT 范围时按文本发送，由服务器转换为 NUMERIC
+        if *self > i64::MAX as u64 {
+            args.push(XuguArgumentValue::Str(Cow::Owned(self.to_string())));
+            return Ok(IsNull::No);
+        }
+
         let buf = self.to_be_bytes().to_vec();
         args.push(XuguArgumentValue::Bin(Cow::Owned(buf)));
 
         Ok(IsNull::No)
     }
+
+    fn produces(&self) -> Option<XuguTypeInfo> {
+        (*self > i64::MAX as u64).then(|| XuguTypeInfo::binary(ColumnType::CHAR))
+    }
 }
 
 fn uint_decode(value: XuguValueRef<'_>) -> Result<u64, BoxDynError> {
@@ -138,6 +152,49 @@ impl Decode<'_, Xugu> for u32 {
 
 impl Decode<'_, Xugu> for u64 {
     fn decode(value: XuguValueRef<'_>) -> Result<Self, BoxDynError> {
+        if value.type_info.r#type == ColumnType::NUMERIC {
+            return decode_wide_integer(value);
+        }
+
         uint_decode(value)
     }
 }
+
+/// 无符号整数数组使用能容纳全部取值的更宽的类型，读取时接受任意整数数组
+macro_rules! impl_uint_array {
+    ($($ty:ty => $array:ident),* $(,)?) => {
+        $(
+            impl XuguHasArrayType for $ty {
+                fn array_type_info() -> XuguTypeInfo {
+                    array_type(ColumnType::$array)
+                }
+
+                fn array_compatible(ty: &XuguTypeInfo) -> bool {
+                    matches!(
+                        ty.r#type,
+                        ColumnType::ARRAY_TINYINT
+                            | ColumnType::ARRAY_SMALLINT
+                            | ColumnType::ARRAY_INTEGER
+                            | ColumnType::ARRAY_BIGINT
+                            | ColumnType::ARRAY_NUMERIC
+                    ) || is_collection(ty.r#type)
+                }
+
+                fn encode_element(&self) -> Result<Option<String>, BoxDynError> {
+                    Ok(Some(self.to_string()))
+                }
+
+                fn decode_element(text: Option<&str>) -> Result<Self, BoxDynError> {
+                    non_null(text)?.trim().parse().map_err(Into::into)
+                }
+            }
+        )*
+    };
+}
+
+// `u8` 没有实现：`Vec<u8>` 表示二进制数据
+impl_uint_array!(
+    u16 => ARRAY_INTEGER,
+    u32 => ARRAY_BIGINT,
+    u64 => ARRAY_NUMERIC,
+);
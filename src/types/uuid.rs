@@ -1,5 +1,6 @@
 use crate::arguments::XuguArgumentValue;
 use crate::protocol::text::{ColumnFlags, ColumnType};
+use crate::types::array::impl_has_array_type;
 use crate::{Xugu, XuguTypeInfo, XuguValueRef};
 use sqlx_core::decode::Decode;
 use sqlx_core::encode::{Encode, IsNull};
@@ -112,3 +113,5 @@ impl<'r> Decode<'r, Xugu> for Simple {
         <Uuid as Decode<Xugu>>::decode(value).map(|u| u.into())
     }
 }
+
+impl_has_array_type!(Uuid => ARRAY_GUID);
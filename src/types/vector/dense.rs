@@ -0,0 +1,126 @@
+use super::{check_finite, fmt_elements, parse_elements};
+use crate::arguments::XuguArgumentValue;
+use crate::protocol::text::ColumnType;
+use crate::{Xugu, XuguTypeInfo, XuguValueRef};
+use sqlx_core::decode::Decode;
+use sqlx_core::encode::{Encode, IsNull};
+use sqlx_core::error::BoxDynError;
+use sqlx_core::types::Type;
+use std::borrow::Cow;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// ## Xugu VECTOR type
+///
+/// 单精度浮点数向量，用于存储嵌入向量并进行相似度检索。
+/// 文本表示为 `[1,2,3]`。
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct XgVector(pub Vec<f32>);
+
+impl XgVector {
+    /// 向量的维数
+    pub fn dim(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn as_slice(&self) -> &[f32] {
+        &self.0
+    }
+}
+
+impl From<Vec<f32>> for XgVector {
+    fn from(value: Vec<f32>) -> Self {
+        Self(value)
+    }
+}
+
+impl From<XgVector> for Vec<f32> {
+    fn from(value: XgVector) -> Self {
+        value.0
+    }
+}
+
+impl Type<Xugu> for XgVector {
+    fn type_info() -> XuguTypeInfo {
+        XuguTypeInfo::binary(ColumnType::VECTOR)
+    }
+
+    fn compatible(ty: &XuguTypeInfo) -> bool {
+        matches!(ty.r#type, ColumnType::VECTOR | ColumnType::CHAR)
+    }
+}
+
+impl Encode<'_, Xugu> for XgVector {
+    fn encode_by_ref(&self, args: &mut Vec<XuguArgumentValue>) -> Result<IsNull, BoxDynError> {
+        check_finite("VECTOR", self.0.iter().copied())?;
+        args.push(XuguArgumentValue::Str(Cow::Owned(self.to_string())));
+
+        Ok(IsNull::No)
+    }
+
+    fn produces(&self) -> Option<XuguTypeInfo> {
+        Some(XuguTypeInfo::binary(ColumnType::CHAR))
+    }
+}
+
+impl<'r> Decode<'r, Xugu> for XgVector {
+    fn decode(value: XuguValueRef<'r>) -> Result<Self, BoxDynError> {
+        Self::from_str(value.as_str()?)
+    }
+}
+
+impl Display for XgVector {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        fmt_elements(f, &self.0)
+    }
+}
+
+impl FromStr for XgVector {
+    type Err = BoxDynError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_elements(s, "VECTOR").map(Self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let vec = XgVector(vec![1.0, -2.5, 0.0, 1e-7, f32::MAX]);
+        let text = vec.to_string();
+        assert_eq!(
+            text,
+            "[1,-2.5,0,0.0000001,340282350000000000000000000000000000000]"
+        );
+        assert_eq!(text.parse::<XgVector>().unwrap(), vec);
+
+        assert_eq!(XgVector::default().to_string(), "[]");
+        assert_eq!(" [ ] ".parse::<XgVector>().unwrap().dim(), 0);
+        assert_eq!(
+            "[ 1 , 2 ]".parse::<XgVector>().unwrap().as_slice(),
+            [1.0, 2.0]
+        );
+    }
+
+    #[test]
+    fn invalid_input() {
+        for s in ["", "1,2", "[1,2", "{1,2}", "[1,,2]", "[a]", "[1;2]"] {
+            assert!(s.parse::<XgVector>().is_err(), "{s:?}");
+        }
+
+        for x in [f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+            let mut args = Vec::new();
+            let Err(err) = XgVector(vec![1.0, x]).encode_by_ref(&mut args) else {
+                panic!("{x} should be rejected");
+            };
+            assert!(
+                err.to_string().contains("element 1 must be finite"),
+                "{err}"
+            );
+            assert!(args.is_empty());
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use super::{check_finite, fmt_elements, parse_elements};
+use crate::arguments::XuguArgumentValue;
+use crate::protocol::text::ColumnType;
+use crate::{Xugu, XuguTypeInfo, XuguValueRef};
+use half::f16;
+use sqlx_core::decode::Decode;
+use sqlx_core::encode::{Encode, IsNull};
+use sqlx_core::error::BoxDynError;
+use sqlx_core::types::Type;
+use std::borrow::Cow;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// ## Xugu HALFVEC type
+///
+/// 半精度浮点数向量，占用空间为 `VECTOR` 的一半。
+/// 文本表示为 `[1,2,3]`。
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct XgHalfVec(pub Vec<f16>);
+
+impl XgHalfVec {
+    /// 向量的维数
+    pub fn dim(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn as_slice(&self) -> &[f16] {
+        &self.0
+    }
+}
+
+impl From<Vec<f16>> for XgHalfVec {
+    fn from(value: Vec<f16>) -> Self {
+        Self(value)
+    }
+}
+
+impl From<XgHalfVec> for Vec<f16> {
+    fn from(value: XgHalfVec) -> Self {
+        value.0
+    }
+}
+
+impl Type<Xugu> for XgHalfVec {
+    fn type_info() -> XuguTypeInfo {
+        XuguTypeInfo::binary(ColumnType::HALFVEC)
+    }
+
+    fn compatible(ty: &XuguTypeInfo) -> bool {
+        matches!(ty.r#type, ColumnType::HALFVEC | ColumnType::CHAR)
+    }
+}
+
+impl Encode<'_, Xugu> for XgHalfVec {
+    fn encode_by_ref(&self, args: &mut Vec<XuguArgumentValue>) -> Result<IsNull, BoxDynError> {
+        check_finite("HALFVEC", self.0.iter().map(|x| x.to_f32()))?;
+        args.push(XuguArgumentValue::Str(Cow::Owned(self.to_string())));
+
+        Ok(IsNull::No)
+    }
+
+    fn produces(&self) -> Option<XuguTypeInfo> {
+        Some(XuguTypeInfo::binary(ColumnType::CHAR))
+    }
+}
+
+impl<'r> Decode<'r, Xugu> for XgHalfVec {
+    fn decode(value: XuguValueRef<'r>) -> Result<Self, BoxDynError> {
+        Self::from_str(value.as_str()?)
+    }
+}
+
+impl Display for XgHalfVec {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        fmt_elements(f, &self.0)
+    }
+}
+
+impl FromStr for XgHalfVec {
+    type Err = BoxDynError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_elements(s, "HALFVEC").map(Self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let vec = XgHalfVec([1.0, -2.5, 0.0, 65504.0].map(f16::from_f32).to_vec());
+        let text = vec.to_string();
+        assert_eq!(text, "[1,-2.5,0,65504]");
+        assert_eq!(text.parse::<XgHalfVec>().unwrap(), vec);
+
+        // 超出半精度的值按最接近的值舍入
+        assert_eq!(
+            "[0.1]".parse::<XgHalfVec>().unwrap().as_slice(),
+            [f16::from_f32(0.1)]
+        );
+        assert_eq!(XgHalfVec::default().to_string(), "[]");
+    }
+
+    #[test]
+    fn invalid_input() {
+        for s in ["", "1,2", "[1,2", "[1,,2]", "[a]"] {
+            assert!(s.parse::<XgHalfVec>().is_err(), "{s:?}");
+        }
+
+        for x in [f16::NAN, f16::INFINITY, f16::NEG_INFINITY] {
+            let mut args = Vec::new();
+            let Err(err) = XgHalfVec(vec![x]).encode_by_ref(&mut args) else {
+                panic!("{x} should be rejected");
+            };
+            assert!(
+                err.to_string().contains("element 0 must be finite"),
+                "{err}"
+            );
+            assert!(args.is_empty());
+        }
+    }
+}
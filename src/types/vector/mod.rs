@@ -0,0 +1,60 @@
+mod dense;
+mod sparsevec;
+
+#[cfg(feature = "half")]
+mod halfvec;
+
+pub use dense::XgVector;
+#[cfg(feature = "half")]
+pub use halfvec::XgHalfVec;
+pub use sparsevec::XgSparseVec;
+
+use sqlx_core::error::BoxDynError;
+use std::fmt::{Display, Formatter, Write};
+use std::str::FromStr;
+
+/// 按 `[1,2,3]` 格式输出向量
+fn fmt_elements<T: Display>(f: &mut Formatter<'_>, elements: &[T]) -> std::fmt::Result {
+    f.write_char('[')?;
+    for (i, x) in elements.iter().enumerate() {
+        if i > 0 {
+            f.write_char(',')?;
+        }
+        write!(f, "{x}")?;
+    }
+    f.write_char(']')
+}
+
+/// 检查向量元素，NaN 和无穷大返回错误
+fn check_finite(name: &str, elements: impl IntoIterator<Item = f32>) -> Result<(), BoxDynError> {
+    match elements
+        .into_iter()
+        .enumerate()
+        .find(|(_, x)| !x.is_finite())
+    {
+        Some((i, x)) => Err(format!("{name} element {i} must be finite, got {x}").into()),
+        None => Ok(()),
+    }
+}
+
+/// 解析 `[1,2,3]` 格式的向量
+fn parse_elements<T: FromStr>(s: &str, name: &str) -> Result<Vec<T>, BoxDynError> {
+    let inner = s
+        .trim()
+        .strip_prefix('[')
+        .and_then(|s| s.strip_suffix(']'))
+        .ok_or_else(|| format!("error decoding {name}: expected `[...]`, got {s:?}"))?;
+
+    if inner.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+
+    inner
+        .split(',')
+        .map(|x| {
+            x.trim()
+                .parse()
+                .map_err(|_| format!("error decoding {name}: invalid element {x:?}").into())
+        })
+        .collect()
+}
@@ -0,0 +1,219 @@
+use super::check_finite;
+use crate::arguments::XuguArgumentValue;
+use crate::protocol::text::ColumnType;
+use crate::{Xugu, XuguTypeInfo, XuguValueRef};
+use sqlx_core::decode::Decode;
+use sqlx_core::encode::{Encode, IsNull};
+use sqlx_core::error::BoxDynError;
+use sqlx_core::types::Type;
+use std::borrow::Cow;
+use std::fmt::{Display, Formatter, Write};
+use std::str::FromStr;
+
+/// ## Xugu SPARSEVEC type
+///
+/// 稀疏向量，只保存非零元素。文本表示为 `{1:1.5,3:2}/5`，
+/// 其中下标从 1 开始，`/` 后为向量的维数。
+///
+/// `indices` 从 0 开始，按升序排列，与 `values` 一一对应。
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct XgSparseVec {
+    /// 向量的维数
+    pub dim: u32,
+    /// 非零元素的下标，从 0 开始
+    pub indices: Vec<u32>,
+    /// 非零元素的值
+    pub values: Vec<f32>,
+}
+
+impl XgSparseVec {
+    /// 由稠密向量创建，忽略其中的零值
+    pub fn from_dense(dense: &[f32]) -> Self {
+        let (indices, values) = dense
+            .iter()
+            .enumerate()
+            .filter(|(_, x)| **x != 0.0)
+            .map(|(i, x)| (i as u32, *x))
+            .unzip();
+
+        Self {
+            dim: dense.len() as u32,
+            indices,
+            values,
+        }
+    }
+
+    /// 转换为稠密向量
+    ///
+    /// 下标超出维数、未按升序排列或与 `values` 的个数不一致时返回错误。
+    pub fn to_dense(&self) -> Result<Vec<f32>, BoxDynError> {
+        self.check()?;
+
+        let mut dense = vec![0.0; self.dim as usize];
+        for (i, x) in self.indices.iter().zip(&self.values) {
+            dense[*i as usize] = *x;
+        }
+        Ok(dense)
+    }
+
+    fn check(&self) -> Result<(), BoxDynError> {
+        if self.indices.len() != self.values.len() {
+            return Err(format!(
+                "SPARSEVEC has {} indices but {} values",
+                self.indices.len(),
+                self.values.len()
+            )
+            .into());
+        }
+
+        let mut prev = None;
+        for &i in &self.indices {
+            if i >= self.dim {
+                return Err(format!(
+                    "SPARSEVEC index {i} out of range for dimension {}",
+                    self.dim
+                )
+                .into());
+            }
+            if prev.is_some_and(|prev| i <= prev) {
+                return Err("SPARSEVEC indices must be strictly increasing".into());
+            }
+            prev = Some(i);
+        }
+
+        Ok(())
+    }
+}
+
+impl Type<Xugu> for XgSparseVec {
+    fn type_info() -> XuguTypeInfo {
+        XuguTypeInfo::binary(ColumnType::SPARSEVEC)
+    }
+
+    fn compatible(ty: &XuguTypeInfo) -> bool {
+        matches!(ty.r#type, ColumnType::SPARSEVEC | ColumnType::CHAR)
+    }
+}
+
+impl Encode<'_, Xugu> for XgSparseVec {
+    fn encode_by_ref(&self, args: &mut Vec<XuguArgumentValue>) -> Result<IsNull, BoxDynError> {
+        self.check()?;
+        check_finite("SPARSEVEC", self.values.iter().copied())?;
+        args.push(XuguArgumentValue::Str(Cow::Owned(self.to_string())));
+
+        Ok(IsNull::No)
+    }
+
+    fn produces(&self) -> Option<XuguTypeInfo> {
+        Some(XuguTypeInfo::binary(ColumnType::CHAR))
+    }
+}
+
+impl<'r> Decode<'r, Xugu> for XgSparseVec {
+    fn decode(value: XuguValueRef<'r>) -> Result<Self, BoxDynError> {
+        Self::from_str(value.as_str()?)
+    }
+}
+
+impl Display for XgSparseVec {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_char('{')?;
+        for (n, (i, x)) in self.indices.iter().zip(&self.values).enumerate() {
+            if n > 0 {
+                f.write_char(',')?;
+            }
+            write!(f, "{}:{}", i + 1, x)?;
+        }
+        write!(f, "}}/{}", self.dim)
+    }
+}
+
+impl FromStr for XgSparseVec {
+    type Err = BoxDynError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("error decoding SPARSEVEC: expected `{{i:x,...}}/dim`, got {s:?}");
+
+        let (elements, dim) = s.trim().rsplit_once('/').ok_or_else(invalid)?;
+        let dim = dim.trim().parse().map_err(|_| invalid())?;
+        let inner = elements
+            .trim()
+            .strip_prefix('{')
+            .and_then(|s| s.strip_suffix('}'))
+            .ok_or_else(invalid)?;
+
+        let mut vec = XgSparseVec {
+            dim,
+            ..Default::default()
+        };
+        if !inner.trim().is_empty() {
+            for element in inner.split(',') {
+                let (i, x) = element.split_once(':').ok_or_else(invalid)?;
+                let i: u32 = i.trim().parse().map_err(|_| invalid())?;
+                let i = i.checked_sub(1).ok_or_else(invalid)?;
+                vec.indices.push(i);
+                vec.values.push(x.trim().parse().map_err(|_| invalid())?);
+            }
+        }
+        vec.check()?;
+
+        Ok(vec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dense_round_trip() {
+        let dense = [0.0, 1.5, 0.0, 0.0, -2.0];
+        let vec = XgSparseVec::from_dense(&dense);
+        assert_eq!(vec.indices, [1, 4]);
+        assert_eq!(vec.to_string(), "{2:1.5,5:-2}/5");
+        assert_eq!(vec.to_dense().unwrap(), dense);
+        assert_eq!(vec.to_string().parse::<XgSparseVec>().unwrap(), vec);
+    }
+
+    #[test]
+    fn to_dense_rejects_invalid_indices() {
+        let invalid = [
+            (3, vec![3], vec![1.0]),
+            (3, vec![u32::MAX], vec![1.0]),
+            (3, vec![1, 1], vec![1.0, 2.0]),
+            (3, vec![2, 1], vec![1.0, 2.0]),
+            (3, vec![0, 1], vec![1.0]),
+        ];
+        for (dim, indices, values) in invalid {
+            let vec = XgSparseVec {
+                dim,
+                indices,
+                values,
+            };
+            assert!(vec.to_dense().is_err(), "{vec:?}");
+        }
+    }
+
+    #[test]
+    fn parse_rejects_out_of_range() {
+        for s in ["{4:1}/3", "{0:1}/3", "{2:1,1:2}/3", "{1:1}", "1:1/3"] {
+            assert!(s.parse::<XgSparseVec>().is_err(), "{s}");
+        }
+        assert_eq!(
+            "{}/0".parse::<XgSparseVec>().unwrap().to_dense().unwrap(),
+            Vec::<f32>::new()
+        );
+    }
+
+    #[test]
+    fn encode_rejects_non_finite() {
+        let vec = XgSparseVec {
+            dim: 3,
+            indices: vec![0, 2],
+            values: vec![1.0, f32::NAN],
+        };
+        let mut args = Vec::new();
+        assert!(vec.encode_by_ref(&mut args).is_err());
+        assert!(args.is_empty());
+    }
+}
@@ -0,0 +1,496 @@
+use crate::arguments::XuguArgumentValue;
+use crate::protocol::text::ColumnType;
+use crate::{Xugu, XuguTypeInfo, XuguValueRef};
+use sqlx_core::decode::Decode;
+use sqlx_core::encode::{Encode, IsNull};
+use sqlx_core::error::BoxDynError;
+use sqlx_core::types::Type;
+use std::fmt::{Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+/// 超过该长度的文档按 `CLOB` 发送，避免超出字符类型的长度上限
+const MAX_CHAR_LEN: usize = 60000;
+
+/// ## Xugu XML type
+///
+/// XML 文档，按文本编解码，较大的文档按 `CLOB` 发送。
+///
+/// 默认不检查文档内容；使用 [`XgXml::validated`] 创建时，编码前会检查文档是否格式良好
+/// （标签是否配对、属性是否加引号、是否只有一个根元素等），不检查 DTD 和命名空间。
+///
+/// ```rust
+/// # use sqlx_xugu::types::XgXml;
+/// let xml = XgXml::validated("<doc id=\"1\"><title>a &amp; b</title></doc>");
+/// assert!(xml.check().is_ok());
+///
+/// let xml = XgXml::validated("<doc><title></doc>");
+/// assert!(xml.check().is_err());
+/// ```
+///
+/// 比较和哈希只使用文档文本，与是否检查内容无关。
+#[derive(Debug, Clone, Default, Eq)]
+pub struct XgXml {
+    xml: String,
+    validate: bool,
+}
+
+impl XgXml {
+    /// 不检查内容
+    pub fn new(xml: impl Into<String>) -> Self {
+        Self {
+            xml: xml.into(),
+            validate: false,
+        }
+    }
+
+    /// 编码前检查文档是否格式良好
+    pub fn validated(xml: impl Into<String>) -> Self {
+        Self {
+            xml: xml.into(),
+            validate: true,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.xml
+    }
+
+    pub fn into_string(self) -> String {
+        self.xml
+    }
+
+    /// 检查文档是否格式良好
+    pub fn check(&self) -> Result<(), BoxDynError> {
+        Checker::new(&self.xml)
+            .document()
+            .map_err(|(pos, msg)| format!("malformed XML at byte {pos}: {msg}").into())
+    }
+}
+
+impl PartialEq for XgXml {
+    fn eq(&self, other: &Self) -> bool {
+        self.xml == other.xml
+    }
+}
+
+impl Hash for XgXml {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.xml.hash(state);
+    }
+}
+
+impl From<String> for XgXml {
+    fn from(value: String) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<&str> for XgXml {
+    fn from(value: &str) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<XgXml> for String {
+    fn from(value: XgXml) -> Self {
+        value.xml
+    }
+}
+
+impl AsRef<str> for XgXml {
+    fn as_ref(&self) -> &str {
+        &self.xml
+    }
+}
+
+impl Type<Xugu> for XgXml {
+    fn type_info() -> XuguTypeInfo {
+        XuguTypeInfo::binary(ColumnType::XML)
+    }
+
+    fn compatible(ty: &XuguTypeInfo) -> bool {
+        matches!(
+            ty.r#type,
+            ColumnType::XML | ColumnType::CHAR | ColumnType::NCHAR | ColumnType::CLOB
+        )
+    }
+}
+
+impl Encode<'_, Xugu> for XgXml {
+    fn encode_by_ref(&self, args: &mut Vec<XuguArgumentValue>) -> Result<IsNull, BoxDynError> {
+        if self.validate {
+            self.check()?;
+        }
+        Encode::<Xugu>::encode_by_ref(&self.xml, args)
+    }
+
+    fn produces(&self) -> Option<XuguTypeInfo> {
+        let ty = if self.xml.len() > MAX_CHAR_LEN {
+            ColumnType::CLOB
+        } else {
+            ColumnType::CHAR
+        };
+        Some(XuguTypeInfo::binary(ty))
+    }
+}
+
+impl<'r> Decode<'r, Xugu> for XgXml {
+    fn decode(value: XuguValueRef<'r>) -> Result<Self, BoxDynError> {
+        <String as Decode<Xugu>>::decode(value).map(Self::new)
+    }
+}
+
+impl Display for XgXml {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.xml)
+    }
+}
+
+/// 解析时检查文档是否格式良好
+impl FromStr for XgXml {
+    type Err = BoxDynError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let xml = Self::validated(s);
+        xml.check()?;
+        Ok(xml)
+    }
+}
+
+type CheckResult<T = ()> = Result<T, (usize, &'static str)>;
+
+/// 简单的格式良好性检查
+struct Checker<'a> {
+    s: &'a str,
+    pos: usize,
+}
+
+impl<'a> Checker<'a> {
+    fn new(s: &'a str) -> Self {
+        Self { s, pos: 0 }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.s[self.pos..]
+    }
+
+    fn err<T>(&self, msg: &'static str) -> CheckResult<T> {
+        Err((self.pos, msg))
+    }
+
+    fn eat(&mut self, prefix: &str) -> bool {
+        if self.rest().starts_with(prefix) {
+            self.pos += prefix.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn skip_ws(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// 跳过到 `end` 之后
+    fn skip_until(&mut self, end: &str, msg: &'static str) -> CheckResult {
+        match self.rest().find(end) {
+            Some(i) => {
+                self.pos += i + end.len();
+                Ok(())
+            }
+            None => self.err(msg),
+        }
+    }
+
+    fn name(&mut self) -> CheckResult<&'a str> {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | ':' | '-' | '.')))
+            .unwrap_or(rest.len());
+        let name = &rest[..len];
+        match name.chars().next() {
+            Some(c) if c.is_alphabetic() || c == '_' || c == ':' => {
+                self.pos += len;
+                Ok(name)
+            }
+            _ => self.err("expected a name"),
+        }
+    }
+
+    fn document(&mut self) -> CheckResult {
+        self.eat("\u{feff}");
+        let mut seen_root = false;
+        loop {
+            self.skip_ws();
+            if self.rest().is_empty() {
+                break;
+            }
+            if self.eat("<?") {
+                self.skip_until("?>", "unterminated processing instruction")?;
+            } else if self.eat("<!--") {
+                self.comment()?;
+            } else if self.eat("<!DOCTYPE") {
+                if seen_root {
+                    return self.err("DOCTYPE after the root element");
+                }
+                self.doctype()?;
+            } else if self.rest().starts_with('<') {
+                if seen_root {
+                    return self.err("more than one root element");
+                }
+                self.element()?;
+                seen_root = true;
+            } else {
+                return self.err("text outside the root element");
+            }
+        }
+
+        if !seen_root {
+            return self.err("no root element");
+        }
+        Ok(())
+    }
+
+    fn comment(&mut self) -> CheckResult {
+        match self.rest().find("--") {
+            Some(i) if self.rest()[i..].starts_with("-->") => {
+                self.pos += i + 3;
+                Ok(())
+            }
+            Some(_) => self.err("`--` inside a comment"),
+            None => self.err("unterminated comment"),
+        }
+    }
+
+    fn doctype(&mut self) -> CheckResult {
+        let mut in_subset = false;
+        for (i, c) in self.rest().char_indices() {
+            match c {
+                '[' => in_subset = true,
+                ']' => in_subset = false,
+                '>' if !in_subset => {
+                    self.pos += i + 1;
+                    return Ok(());
+                }
+                _ => {}
+            }
+        }
+        self.err("unterminated DOCTYPE")
+    }
+
+    /// 元素，包括其中的所有内容
+    fn element(&mut self) -> CheckResult {
+        let mut stack = Vec::new();
+        loop {
+            // start or empty-element tag
+            self.eat("<");
+            let name = self.name()?;
+            self.attributes()?;
+            if self.eat("/>") {
+                if stack.is_empty() {
+                    return Ok(());
+                }
+            } else if self.eat(">") {
+                stack.push(name);
+            } else {
+                return self.err("expected `>` or `/>`");
+            }
+
+            // content up to the next start tag
+            loop {
+                self.text()?;
+                if self.eat("</") {
+                    let name = self.name()?;
+                    self.skip_ws();
+                    if !self.eat(">") {
+                        return self.err("expected `>`");
+                    }
+                    if stack.pop() != Some(name) {
+                        return self.err("mismatched end tag");
+                    }
+                    if stack.is_empty() {
+                        return Ok(());
+                    }
+                } else if self.eat("<!--") {
+                    self.comment()?;
+                } else if self.eat("<![CDATA[") {
+                    self.skip_until("]]>", "unterminated CDATA section")?;
+                } else if self.eat("<?") {
+                    self.skip_until("?>", "unterminated processing instruction")?;
+                } else if self.rest().starts_with('<') {
+                    break;
+                } else {
+                    return self.err("unterminated element");
+                }
+            }
+        }
+    }
+
+    fn attributes(&mut self) -> CheckResult {
+        let mut names = Vec::new();
+        loop {
+            let before = self.pos;
+            self.skip_ws();
+            if self.rest().starts_with('>') || self.rest().starts_with("/>") {
+                return Ok(());
+            }
+            if self.pos == before {
+                return self.err("expected whitespace before an attribute");
+            }
+
+            let name = self.name()?;
+            if names.contains(&name) {
+                return self.err("duplicate attribute");
+            }
+            names.push(name);
+
+            self.skip_ws();
+            if !self.eat("=") {
+                return self.err("expected `=` after an attribute name");
+            }
+            self.skip_ws();
+            let quote = match self.rest().chars().next() {
+                Some(q @ ('"' | '\'')) => q,
+                _ => return self.err("attribute value must be quoted"),
+            };
+            self.pos += 1;
+            let end = match self.rest().find(quote) {
+                Some(end) => end,
+                None => return self.err("unterminated attribute value"),
+            };
+            let value = &self.rest()[..end];
+            if value.contains('<') {
+                return self.err("`<` in an attribute value");
+            }
+            self.references(value)?;
+            self.pos += end + 1;
+        }
+    }
+
+    /// 字符数据，直到下一个 `<`
+    fn text(&mut self) -> CheckResult {
+        let rest = self.rest();
+        let end = rest.find('<').unwrap_or(rest.len());
+        let text = &rest[..end];
+        if text.contains("]]>") {
+            return self.err("`]]>` in character data");
+        }
+        self.references(text)?;
+        self.pos += end;
+        Ok(())
+    }
+
+    /// 检查 `&name;`、`&#123;`、`&#x7b;` 形式的引用
+    fn references(&self, text: &str) -> CheckResult {
+        let mut rest = text;
+        while let Some(i) = rest.find('&') {
+            rest = &rest[i + 1..];
+            let end = match rest.find(';') {
+                Some(end) => end,
+                None => return self.err("unterminated entity reference"),
+            };
+            let entity = &rest[..end];
+            let valid = if let Some(hex) = entity.strip_prefix("#x") {
+                !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit())
+            } else if let Some(dec) = entity.strip_prefix('#') {
+                !dec.is_empty() && dec.chars().all(|c| c.is_ascii_digit())
+            } else {
+                Checker::new(entity).name().map(str::len) == Ok(entity.len())
+            };
+            if !valid {
+                return self.err("invalid entity reference");
+            }
+            rest = &rest[end + 1..];
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn check(xml: &str) -> Result<(), String> {
+        XgXml::validated(xml).check().map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn well_formed() {
+        for xml in [
+            "<a/>",
+            "<a></a>",
+            "\u{feff}<?xml version=\"1.0\"?>\n<!-- c --><a/>\n",
+            "<!DOCTYPE a [<!ELEMENT a (#PCDATA)>]><a>x</a>",
+            "<a><b/><c x='1' y=\"2\">t</c ></a>",
+            "<a>&lt;&#60;&#x3C;&amp;</a>",
+            "<a><!-- x - y --><!----></a>",
+            "<a><![CDATA[<b> & ]] > </a>]]></a>",
+            "<a><?pi data > more?></a>",
+            "<a x=\"1 > 0\" y='/>'>&gt;</a>",
+            "<中文 属性=\"值\">文本</中文>",
+            "<a><a><a/></a></a><!-- trailing -->",
+        ] {
+            assert_eq!(check(xml), Ok(()), "{xml}");
+        }
+    }
+
+    #[test]
+    fn malformed() {
+        let cases = [
+            ("", "no root element"),
+            ("<a><b></a></b>", "mismatched end tag"),
+            ("<a></b>", "mismatched end tag"),
+            ("<a>", "unterminated element"),
+            ("<a><b></b>", "unterminated element"),
+            ("<a><b/>text", "unterminated element"),
+            ("<a/><b/>", "more than one root element"),
+            ("text<a/>", "text outside the root element"),
+            ("<a/>text", "text outside the root element"),
+            ("<a><!-- a -- b --></a>", "`--` inside a comment"),
+            ("<a><!-- a ---></a>", "`--` inside a comment"),
+            ("<a><!-- a </a>", "unterminated comment"),
+            ("<a><![CDATA[x</a>", "unterminated CDATA section"),
+            ("<![CDATA[x]]><a/>", "expected a name"),
+            ("<a>]]></a>", "`]]>` in character data"),
+            ("<a><?pi </a>", "unterminated processing instruction"),
+            (
+                "<?xml version=\"1.0\"",
+                "unterminated processing instruction",
+            ),
+            ("<a x=1/>", "attribute value must be quoted"),
+            ("<a x=\"1/>", "unterminated attribute value"),
+            ("<a x=\"<\"/>", "`<` in an attribute value"),
+            ("<a x='1' x='2'/>", "duplicate attribute"),
+            ("<a x='1'y='2'/>", "expected whitespace before an attribute"),
+            ("<a x/>", "expected `=` after an attribute name"),
+            ("<a>&amp</a>", "unterminated entity reference"),
+            ("<a>&#xZ;</a>", "invalid entity reference"),
+            ("<1a/>", "expected a name"),
+            ("<a/><!DOCTYPE a>", "DOCTYPE after the root element"),
+        ];
+        for (xml, msg) in cases {
+            let err = check(xml).unwrap_err();
+            assert!(err.contains(msg), "{xml}: {err}");
+        }
+    }
+
+    #[test]
+    fn from_str_validates() {
+        assert!("<a/>".parse::<XgXml>().is_ok());
+        assert!("<a>".parse::<XgXml>().is_err());
+        // 不检查时可以保存任意文本
+        assert_eq!(XgXml::new("<a>").as_str(), "<a>");
+    }
+
+    #[test]
+    fn eq_ignores_validate() {
+        assert_eq!(XgXml::new("<a/>"), XgXml::validated("<a/>"));
+        assert_ne!(XgXml::new("<a/>"), XgXml::new("<a />"));
+
+        let set: HashSet<XgXml> = [XgXml::new("<a/>"), XgXml::validated("<a/>")].into();
+        assert_eq!(set.len(), 1);
+    }
+}
@@ -11,6 +11,8 @@ use crate::{Xugu, XuguTypeInfo};
 pub struct XuguValue {
     pub(crate) value: Option<Bytes>,
     pub(crate) type_info: XuguTypeInfo,
+    /// 会话时区（相对 UTC 的秒数），用于解码不带时区的日期时间
+    pub(crate) time_zone: Option<i32>,
 }
 
 /// Implementation of [`ValueRef`] for Xugu.
@@ -19,6 +21,8 @@ pub struct XuguValueRef<'r> {
     pub(crate) value: Option<&'r [u8]>,
     pub(crate) row: Option<&'r Vec<Bytes>>,
     pub(crate) type_info: XuguTypeInfo,
+    /// 会话时区（相对 UTC 的秒数），用于解码不带时区的日期时间
+    pub(crate) time_zone: Option<i32>,
 }
 
 impl<'r> XuguValueRef<'r> {
@@ -42,6 +46,7 @@ impl Value for XuguValue {
             value: self.value.as_deref(),
             row: None,
             type_info: self.type_info.clone(),
+            time_zone: self.time_zone,
         }
     }
 
@@ -118,6 +123,7 @@ impl<'r> ValueRef<'r> for XuguValueRef<'r> {
         XuguValue {
             value,
             type_info: self.type_info.clone(),
+            time_zone: self.time_zone,
         }
     }
 